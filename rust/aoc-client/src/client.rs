@@ -0,0 +1,258 @@
+use std::{fmt, fs, path::Path, time::Duration};
+
+use aoc_common::{input::input_path, Part};
+
+use crate::{
+    config::Config,
+    error::{Error, Result},
+    http::{self, Request, Response},
+};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u16 = 2023;
+
+// What the site made of a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    Wait(Option<Duration>),
+    AlreadySolved,
+    Unrecognized(String),
+}
+
+impl Submission {
+    fn parse(html: &str) -> Self {
+        let text = article_text(html);
+
+        if text.contains("That's the right answer") {
+            Submission::Correct
+        } else if text.contains("your answer is too high") {
+            Submission::TooHigh
+        } else if text.contains("your answer is too low") {
+            Submission::TooLow
+        } else if text.contains("That's not the right answer") {
+            Submission::Wrong
+        } else if text.contains("You gave an answer too recently") {
+            Submission::Wait(wait_time(&text))
+        } else if text.contains("Did you already complete it") {
+            Submission::AlreadySolved
+        } else {
+            Submission::Unrecognized(text.trim().to_string())
+        }
+    }
+}
+
+impl fmt::Display for Submission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Submission::Correct            => write!(f, "correct"),
+            Submission::TooHigh            => write!(f, "wrong (too high)"),
+            Submission::TooLow             => write!(f, "wrong (too low)"),
+            Submission::Wrong              => write!(f, "wrong"),
+            Submission::Wait(Some(wait))   => write!(f, "submitted too recently, wait {}s", wait.as_secs()),
+            Submission::Wait(None)         => write!(f, "submitted too recently"),
+            Submission::AlreadySolved      => write!(f, "already solved"),
+            Submission::Unrecognized(text) => write!(f, "unrecognized reply: {}", text),
+        }
+    }
+}
+
+// Parses the "You have 1m 5s left to wait" part of a rate-limit reply.
+fn wait_time(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (amount, _) = rest.split_once(" left to wait")?;
+
+    amount.split_whitespace().try_fold(0, |seconds, unit| {
+        let (value, scale) = match unit.strip_suffix('m') {
+            Some(minutes) => (minutes, 60),
+            None          => (unit.strip_suffix('s')?, 1),
+        };
+        Some(seconds + value.parse::<u64>().ok()? * scale)
+    }).map(Duration::from_secs)
+}
+
+// The readable text of a page's `<article>` elements (the whole page when it
+// has none), with tags dropped, block ends turned into newlines and the
+// common entities decoded.
+pub fn article_text(html: &str) -> String {
+    let mut articles = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        let end = rest[start..].find("</article>").map_or(rest.len(), |end| start + end);
+        articles.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    if articles.is_empty() {
+        articles.push(html);
+    }
+
+    let mut text = String::new();
+    for article in articles {
+        let mut rest = article;
+        while let Some(open) = rest.find('<') {
+            text += &rest[..open];
+            let Some(close) = rest[open..].find('>') else { break };
+            let tag = &rest[open + 1..open + close];
+            if matches!(tag, "/p" | "/h2" | "/pre" | "/li" | "/ul" | "br") {
+                text.push('\n');
+            }
+            rest = &rest[open + close + 1..];
+        }
+        text += rest.split('<').next().unwrap_or("");
+        text.push('\n');
+    }
+
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&#39;", "'").replace("&amp;", "&")
+}
+
+// Talks to the puzzle site at `base_url`, which is the real one unless
+// configured otherwise (e.g. pointed at the mock server).
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self { base_url: base_url.into().trim_end_matches('/').to_string(), session: None }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let client = Self::new(config.base_url.as_deref().unwrap_or(BASE_URL));
+        match &config.session {
+            Some(session) => client.with_session(session.clone()),
+            None          => client,
+        }
+    }
+
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(session.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request(&self, method: &'static str, path: &str, form: Option<String>, needs_session: bool) -> Result<String> {
+        let cookie = match (&self.session, needs_session) {
+            (Some(session), _) => Some(format!("session={}", session)),
+            (None, true)       => return Err(Error::MissingSession),
+            (None, false)      => None,
+        };
+        let request = Request { method, url: format!("{}/{}/day/{}", self.base_url, YEAR, path), cookie, form };
+
+        match http::send(&request)? {
+            Response { status: 200, body } => Ok(body),
+            Response { status, body }      => Err(Error::Http { status, body: article_text(&body) }),
+        }
+    }
+
+    pub fn input(&self, day: u8) -> Result<String> {
+        self.request("GET", &format!("{}/input", day), None, true)
+    }
+
+    // The day's input from `dir`, downloading it into `dir/dayNN.txt` first
+    // if it is not there yet, so an input is never fetched twice. Reports
+    // whether a download happened.
+    pub fn cached_input(&self, day: u8, dir: &Path) -> Result<(String, bool)> {
+        let path = input_path(dir, day);
+        if path.exists() {
+            return Ok((fs::read_to_string(&path)?, false));
+        }
+
+        let contents = self.input(day)?;
+        fs::create_dir_all(dir)?;
+        fs::write(&path, &contents)?;
+        Ok((contents, true))
+    }
+
+    // The puzzle description as plain text.
+    pub fn puzzle(&self, day: u8) -> Result<String> {
+        self.request("GET", &day.to_string(), None, false).map(|html| article_text(&html))
+    }
+
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Submission> {
+        let form = format!("level={}&answer={}", part.number(), http::encode(answer));
+        let html = self.request("POST", &format!("{}/answer", day), Some(form), true)?;
+        Ok(Submission::parse(&html))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockServer, Puzzles};
+
+    fn server() -> MockServer {
+        let puzzles = Puzzles::default()
+            .input(1, "1abc2\npqr3stu8vwx\n")
+            .answer(1, Part::One, "45");
+        MockServer::start("127.0.0.1:0", puzzles).unwrap()
+    }
+
+    #[test]
+    fn inputs_are_cached_and_fetched_once() {
+        let server = server();
+        let client = Client::new(server.url()).with_session("token");
+        let dir = std::env::temp_dir().join(format!("aoc-client-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(client.cached_input(1, &dir).unwrap(), ("1abc2\npqr3stu8vwx\n".to_string(), true));
+        assert_eq!(client.cached_input(1, &dir).unwrap(), ("1abc2\npqr3stu8vwx\n".to_string(), false));
+        assert_eq!(server.hits("/2023/day/1/input"), 1);
+        assert_eq!(fs::read_to_string(dir.join("day01.txt")).unwrap(), "1abc2\npqr3stu8vwx\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn inputs_need_a_session() {
+        let server = server();
+
+        assert!(matches!(Client::new(server.url()).input(1), Err(Error::MissingSession)));
+        let err = Client::new(server.url()).with_session("token").input(2).unwrap_err();
+        assert!(matches!(err, Error::Http { status: 404, .. }));
+    }
+
+    #[test]
+    fn submissions_follow_the_site() {
+        let server = server();
+        let client = Client::new(server.url()).with_session("token");
+
+        assert_eq!(client.submit(1, Part::One, "50").unwrap(), Submission::TooHigh);
+        assert!(matches!(client.submit(1, Part::One, "45").unwrap(), Submission::Wait(Some(wait)) if wait.as_secs() > 0));
+        assert_eq!(client.puzzle(1).unwrap().lines().next(), Some("--- Day 1: Mock Puzzle ---"));
+    }
+
+    #[test]
+    fn correct_answers_are_final() {
+        let puzzles = Puzzles::default().input(1, "").answer(1, Part::One, "45").cooldown(Duration::ZERO);
+        let server = MockServer::start("127.0.0.1:0", puzzles).unwrap();
+        let client = Client::new(server.url()).with_session("token");
+
+        assert_eq!(client.submit(1, Part::One, "44").unwrap(), Submission::TooLow);
+        assert_eq!(client.submit(1, Part::One, "45").unwrap(), Submission::Correct);
+        assert_eq!(client.submit(1, Part::One, "45").unwrap(), Submission::AlreadySolved);
+    }
+
+    #[test]
+    fn a_silent_client_holds_up_no_one() {
+        let server = server();
+        let _silent = std::net::TcpStream::connect(server.url().trim_start_matches("http://")).unwrap();
+
+        let client = Client::new(server.url()).with_session("token");
+        assert_eq!(client.puzzle(1).unwrap().lines().next(), Some("--- Day 1: Mock Puzzle ---"));
+    }
+
+    #[test]
+    fn parses_wait_times() {
+        assert_eq!(wait_time("You have 1m 5s left to wait."), Some(Duration::from_secs(65)));
+        assert_eq!(wait_time("You have 42s left to wait."), Some(Duration::from_secs(42)));
+        assert_eq!(wait_time("Please wait."), None);
+    }
+}
@@ -0,0 +1,77 @@
+use std::{env, path::PathBuf};
+
+use aoc_common::{read_file, Error, Result, Source};
+
+pub const CONFIG_VAR: &str = "AOC_CONFIG";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+// Settings for talking to the puzzle site, read as `key = value` lines from
+// the config file and overridden by the environment.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn parse(contents: &str) -> Result<Self> {
+        let source = Source::new(contents);
+        let mut config = Self::default();
+
+        for line in source.lines().filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#')) {
+            let (key, value) = source.split_once(line, "=")?;
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "session"  => config.session = value,
+                "base_url" => config.base_url = value,
+                _          => return Err(source.error(key.trim(), "`session` or `base_url`")),
+            }
+        }
+
+        Ok(config)
+    }
+
+    // `$AOC_CONFIG`, or else `~/.config/aoc/config`.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os(CONFIG_VAR).map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config/aoc/config")))
+    }
+
+    // The config file if there is one, with `$AOC_SESSION` and `$AOC_BASE_URL`
+    // taking precedence over it.
+    pub fn load() -> Result<Self> {
+        let mut config = match Self::path().map(read_file) {
+            Some(Ok(contents))                   => Self::parse(&contents)?,
+            Some(Err(Error::Io { source, .. })) if source.kind() == std::io::ErrorKind::NotFound
+                                                 => Self::default(),
+            Some(Err(err))                       => return Err(err),
+            None                                 => Self::default(),
+        };
+
+        if let Ok(session) = env::var(SESSION_VAR) {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var(BASE_URL_VAR) {
+            config.base_url = Some(base_url);
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_value_lines() {
+        let config = Config::parse("# puzzle site\nsession = 53616c7465645f5f\n\nbase_url=http://127.0.0.1:8023\n").unwrap();
+        assert_eq!(config, Config {
+            session: Some("53616c7465645f5f".to_string()),
+            base_url: Some("http://127.0.0.1:8023".to_string()),
+        });
+
+        let err = Config::parse("session = x\ntoken = y\n").unwrap_err();
+        assert!(err.to_string().starts_with("parse error: line 2, column 1"));
+    }
+}
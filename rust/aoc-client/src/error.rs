@@ -0,0 +1,39 @@
+use std::{fmt, io};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Url(String),
+    Http { status: u16, body: String },
+    Protocol(String),
+    MissingSession,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err)               => write!(f, "{}", err),
+            Error::Url(url)              => write!(f, "unsupported URL '{}'", url),
+            Error::Http { status, body } => write!(f, "server answered {}: {}", status, body.trim()),
+            Error::Protocol(msg)         => write!(f, "malformed response: {}", msg),
+            Error::MissingSession        => write!(f, "no session token (set AOC_SESSION or `session` in the config file)"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _              => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use crate::error::{Error, Result};
+
+const USER_AGENT: &str = "aoc-2023 runner (github.com/kpindur/aoc-2023)";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: &'static str,
+    pub url: String,
+    pub cookie: Option<String>,
+    pub form: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url<'a> {
+    pub host: &'a str,
+    pub port: u16,
+    pub path: &'a str,
+}
+
+impl<'a> Url<'a> {
+    // Only plain `http://host[:port]/path` URLs; TLS is left to curl.
+    pub fn parse(url: &'a str) -> Result<Self> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| Error::Url(url.to_string()))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None    => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| Error::Url(url.to_string()))?),
+            None               => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(Error::Url(url.to_string()));
+        }
+        Ok(Self { host, port, path })
+    }
+}
+
+// Sends `request`, speaking HTTP/1.1 directly for `http://` URLs and handing
+// `https://` ones to the system curl, so the workspace needs no TLS crate.
+pub fn send(request: &Request) -> Result<Response> {
+    if request.url.starts_with("https://") {
+        send_curl(request)
+    } else {
+        send_plain(request)
+    }
+}
+
+fn send_plain(request: &Request) -> Result<Response> {
+    let url = Url::parse(&request.url)?;
+    let mut stream = TcpStream::connect((url.host, url.port))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: {}:{}\r\nUser-Agent: {}\r\nConnection: close\r\n",
+        request.method, url.path, url.host, url.port, USER_AGENT,
+    );
+    if let Some(cookie) = &request.cookie {
+        head += &format!("Cookie: {}\r\n", cookie);
+    }
+    let body = request.form.as_deref().unwrap_or("");
+    if request.form.is_some() {
+        head += "Content-Type: application/x-www-form-urlencoded\r\n";
+    }
+    head += &format!("Content-Length: {}\r\n\r\n", body.len());
+
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body.as_bytes())?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    parse_response(&raw)
+}
+
+fn parse_response(raw: &[u8]) -> Result<Response> {
+    let text = String::from_utf8_lossy(raw);
+    let (head, body) = text.split_once("\r\n\r\n")
+        .ok_or_else(|| Error::Protocol("no end of headers".to_string()))?;
+    let mut lines = head.lines();
+
+    let status = lines.next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| Error::Protocol("bad status line".to_string()))?;
+    let chunked = lines.filter_map(|line| line.split_once(':'))
+        .any(|(name, value)| name.eq_ignore_ascii_case("transfer-encoding") && value.trim().eq_ignore_ascii_case("chunked"));
+
+    let body = if chunked { dechunk(body)? } else { body.to_string() };
+    Ok(Response { status, body })
+}
+
+fn dechunk(mut body: &str) -> Result<String> {
+    let mut out = String::new();
+
+    loop {
+        let (size, rest) = body.split_once("\r\n")
+            .ok_or_else(|| Error::Protocol("truncated chunk".to_string()))?;
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| Error::Protocol(format!("bad chunk size '{}'", size)))?;
+        if size == 0 {
+            return Ok(out);
+        }
+        let chunk = rest.get(..size).ok_or_else(|| Error::Protocol("truncated chunk".to_string()))?;
+        out += chunk;
+        body = rest[size..].strip_prefix("\r\n").unwrap_or(&rest[size..]);
+    }
+}
+
+// A quoted value in a curl config file.
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            },
+            '\n'       => quoted.push_str("\\n"),
+            '\r'       => quoted.push_str("\\r"),
+            '\t'       => quoted.push_str("\\t"),
+            _          => quoted.push(c),
+        }
+    }
+    quoted + "\""
+}
+
+// The curl invocation for `request`. The cookie and the form go in the config
+// curl reads from stdin, never on the command line, where any local user
+// could read the session token through `ps`.
+fn curl_command(request: &Request) -> (Command, String) {
+    let mut command = Command::new("curl");
+    command.args(["--silent", "--show-error", "--max-time", "30"])
+        .args(["--user-agent", USER_AGENT])
+        .args(["--request", request.method])
+        .args(["--write-out", "\n%{http_code}"])
+        .args(["--config", "-"])
+        .arg(&request.url);
+
+    let mut config = String::new();
+    if let Some(cookie) = &request.cookie {
+        config += &format!("header = {}\n", quote(&format!("Cookie: {}", cookie)));
+    }
+    if let Some(form) = &request.form {
+        config += &format!("data-binary = {}\n", quote(form));
+    }
+    (command, config)
+}
+
+fn send_curl(request: &Request) -> Result<Response> {
+    let (mut command, config) = curl_command(request);
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Take stdin so it is closed once the config is written.
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(Error::Protocol(format!("curl failed: {}", String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = text.rsplit_once('\n')
+        .ok_or_else(|| Error::Protocol("curl printed no status".to_string()))?;
+    let status = status.trim().parse().map_err(|_| Error::Protocol(format!("bad status '{}'", status)))?;
+    Ok(Response { status, body: body.to_string() })
+}
+
+// `application/x-www-form-urlencoded` escaping of a single value.
+pub fn encode(value: &str) -> String {
+    value.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+        b' '                                                                => "+".to_string(),
+        _                                                                   => format!("%{:02X}", byte),
+    }).collect()
+}
+
+pub fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+'                        => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    },
+                    None       => out.push(b'%'),
+                }
+            },
+            byte                        => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_urls() {
+        assert_eq!(Url::parse("http://127.0.0.1:8023/2023/day/1").unwrap(), Url { host: "127.0.0.1", port: 8023, path: "/2023/day/1" });
+        assert_eq!(Url::parse("http://localhost").unwrap(), Url { host: "localhost", port: 80, path: "/" });
+        assert!(Url::parse("ftp://localhost").is_err());
+        assert!(Url::parse("http://:80/").is_err());
+    }
+
+    #[test]
+    fn decodes_chunked_bodies() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n";
+        assert_eq!(parse_response(raw).unwrap(), Response { status: 200, body: "hello world".to_string() });
+    }
+
+    #[test]
+    fn curl_gets_the_session_on_stdin() {
+        let request = Request {
+            method: "POST",
+            url: "https://adventofcode.com/2023/day/1/answer".to_string(),
+            cookie: Some("session=53cr3t".to_string()),
+            form: Some("level=1&answer=\"142\"".to_string()),
+        };
+        let (command, config) = curl_command(&request);
+
+        assert!(command.get_args().all(|arg| !arg.to_string_lossy().contains("53cr3t")));
+        assert_eq!(config, "header = \"Cookie: session=53cr3t\"\ndata-binary = \"level=1&answer=\\\"142\\\"\"\n");
+    }
+
+    #[test]
+    fn form_values_round_trip() {
+        for value in ["123", "-42", "ab c&d=e", "100%"] {
+            assert_eq!(decode(&encode(value)), value);
+        }
+    }
+}
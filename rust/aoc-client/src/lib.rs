@@ -0,0 +1,12 @@
+//! Client for the puzzle site: downloads inputs, fetches puzzle pages and
+//! submits answers, plus a local mock of the same endpoints for offline use.
+
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod http;
+pub mod mock;
+
+pub use client::{Client, Submission};
+pub use config::Config;
+pub use error::{Error, Result};
@@ -0,0 +1,259 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use aoc_common::Part;
+
+use crate::http::decode;
+
+// How long a connection may go without sending anything before it is
+// dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+// What the mock site knows: each day's input, the answers it accepts, and how
+// long a wrong answer locks out further submissions.
+#[derive(Debug, Clone)]
+pub struct Puzzles {
+    inputs: BTreeMap<u8, String>,
+    answers: BTreeMap<(u8, Part), String>,
+    cooldown: Duration,
+}
+
+impl Default for Puzzles {
+    fn default() -> Self {
+        Self { inputs: BTreeMap::new(), answers: BTreeMap::new(), cooldown: Duration::from_secs(60) }
+    }
+}
+
+impl Puzzles {
+    pub fn input(mut self, day: u8, contents: impl Into<String>) -> Self {
+        self.inputs.insert(day, contents.into());
+        self
+    }
+
+    pub fn answer(mut self, day: u8, part: Part, answer: impl Into<String>) -> Self {
+        self.answers.insert((day, part), answer.into());
+        self
+    }
+
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn days(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn answers(&self) -> usize {
+        self.answers.len()
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    hits: HashMap<String, usize>,
+    solved: BTreeSet<(u8, Part)>,
+    locked_until: Option<Instant>,
+}
+
+// A local stand-in for the puzzle site, serving the same endpoints and the
+// same wording as the real one so the client can be exercised offline.
+// Stops when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    // Binds `addr` (port 0 picks a free one) and serves from a background thread.
+    pub fn start(addr: &str, puzzles: Puzzles) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let (state, stop, puzzles) = (state.clone(), stop.clone(), Arc::new(puzzles));
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    // Each connection gets a thread of its own, so a client
+                    // that hangs up or goes quiet only affects its request.
+                    if let Ok(stream) = stream {
+                        let (puzzles, state) = (puzzles.clone(), state.clone());
+                        std::thread::spawn(move || {
+                            let _ = stream.set_read_timeout(Some(READ_TIMEOUT))
+                                .and_then(|()| handle(stream, &puzzles, &state));
+                        });
+                    }
+                }
+            })
+        };
+
+        Ok(Self { addr, state, stop, thread: Some(thread) })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    // How many requests have been made for `path`.
+    pub fn hits(&self, path: &str) -> usize {
+        self.state.lock().unwrap().hits.get(path).copied().unwrap_or(0)
+    }
+
+    // Serves until the process is killed.
+    pub fn wait(mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            // Wake the accept loop so it sees the flag.
+            let _ = TcpStream::connect(self.addr);
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    session: bool,
+    body: String,
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut words = line.split_whitespace();
+    let method = words.next().unwrap_or("").to_string();
+    let path = words.next().unwrap_or("").to_string();
+
+    let mut length = 0;
+    let mut session = false;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else { continue };
+        if name.eq_ignore_ascii_case("content-length") {
+            length = value.trim().parse::<usize>().unwrap_or(0).min(1 << 20);
+        } else if name.eq_ignore_ascii_case("cookie") {
+            session |= value.split(';').any(|cookie| matches!(cookie.trim().split_once('='), Some(("session", token)) if !token.is_empty()));
+        }
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Request { method, path, session, body: String::from_utf8_lossy(&body).into_owned() })
+}
+
+fn handle(mut stream: TcpStream, puzzles: &Puzzles, state: &Mutex<State>) -> io::Result<()> {
+    let request = read_request(&stream)?;
+    *state.lock().unwrap().hits.entry(request.path.clone()).or_default() += 1;
+
+    let (status, body) = route(&request, puzzles, state);
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _   => "Method Not Allowed",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body,
+    )
+}
+
+fn page(message: &str) -> String {
+    format!("<!DOCTYPE html>\n<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>\n", message)
+}
+
+fn route(request: &Request, puzzles: &Puzzles, state: &Mutex<State>) -> (u16, String) {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let day = match segments[..] {
+        [_, "day", day, ..] => day.parse::<u8>().ok(),
+        _                   => None,
+    };
+    let Some(day) = day.filter(|day| puzzles.inputs.contains_key(day)) else {
+        return (404, "404 Not Found".to_string());
+    };
+
+    match (request.method.as_str(), &segments[3..]) {
+        ("GET", [])            => (200, format!(
+            "<!DOCTYPE html>\n<html><body><main>\n<article class=\"day-desc\"><h2>--- Day {}: Mock Puzzle ---</h2>\
+             <p>This puzzle is served by the local mock.</p><p>Its input is {} bytes long.</p></article>\n</main></body></html>\n",
+            day, puzzles.inputs[&day].len(),
+        )),
+        ("GET", ["input"])     => match request.session {
+            true  => (200, puzzles.inputs[&day].clone()),
+            false => (400, "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n".to_string()),
+        },
+        ("POST", ["answer"])   => match request.session {
+            true  => (200, page(&submit(day, &request.body, puzzles, &mut state.lock().unwrap()))),
+            false => (400, "Please log in.\n".to_string()),
+        },
+        _                      => (405, "Method Not Allowed".to_string()),
+    }
+}
+
+fn submit(day: u8, form: &str, puzzles: &Puzzles, state: &mut State) -> String {
+    let mut level = None;
+    let mut answer = String::new();
+    for (key, value) in form.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "level"  => level = match value { "1" => Some(Part::One), "2" => Some(Part::Two), _ => None },
+            "answer" => answer = decode(value),
+            _        => (),
+        }
+    }
+
+    let now = Instant::now();
+    if let Some(until) = state.locked_until.filter(|&until| until > now) {
+        let left = (until - now).as_secs() + 1;
+        return format!(
+            "You gave an answer too recently; you have to wait after submitting an answer before trying again.  \
+             You have {}{}s left to wait.",
+            if left >= 60 { format!("{}m ", left / 60) } else { String::new() }, left % 60,
+        );
+    }
+
+    let expected = level.and_then(|part| puzzles.answers.get(&(day, part)).map(|answer| (part, answer)));
+    let Some((part, expected)) = expected.filter(|&(part, _)| !state.solved.contains(&(day, part))) else {
+        return "You don't seem to be solving the right level.  Did you already complete it?".to_string();
+    };
+    if answer.trim() == expected {
+        state.solved.insert((day, part));
+        return "That's the right answer!  You are one gold star closer to restoring snow operations.".to_string();
+    }
+
+    state.locked_until = Some(now + puzzles.cooldown);
+    let hint = match (answer.trim().parse::<i128>(), expected.parse::<i128>()) {
+        (Ok(given), Ok(expected)) if given > expected => "; your answer is too high",
+        (Ok(given), Ok(expected)) if given < expected => "; your answer is too low",
+        _                                             => "",
+    };
+    format!("That's not the right answer{}.  Please wait one minute before trying again.", hint)
+}
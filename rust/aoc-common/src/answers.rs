@@ -0,0 +1,194 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    error::{Error, Result},
+    input::read_file,
+    parse::Source,
+    solution::{Answer, Part},
+};
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+// FNV-1a over the raw input. Stable across platforms and Rust versions, which
+// `DefaultHasher` does not promise.
+pub fn fingerprint(contents: &str) -> String {
+    let hash = contents.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Unknown,
+    Match,
+    Mismatch { expected: String },
+}
+
+// Backslashes, tabs and line breaks in a stored answer are written as `\\`,
+// `\t`, `\n` and `\r`, so that every answer stays on one line of its own.
+fn escape(answer: &str) -> String {
+    let mut escaped = String::with_capacity(answer.len());
+    for c in answer.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _    => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// The answer `escape` wrote, `None` if `text` has an escape it never writes.
+fn unescape(text: &str) -> Option<String> {
+    let mut answer = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        answer.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                't'  => '\t',
+                'n'  => '\n',
+                'r'  => '\r',
+                _    => return None,
+            },
+            _    => c,
+        });
+    }
+    Some(answer)
+}
+
+// Known answers keyed by day, part and input fingerprint, stored one per line
+// as `day<TAB>part<TAB>fingerprint<TAB>answer`, the answer escaped. Lines
+// starting with `#` are ignored.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AnswerBook {
+    entries: BTreeMap<(u8, Part, String), String>,
+}
+
+impl AnswerBook {
+    pub fn parse(contents: &str) -> Result<Self> {
+        let source = Source::new(contents);
+        let mut book = Self::default();
+
+        for line in source.lines().filter(|line| !line.trim().is_empty() && !line.starts_with('#')) {
+            let fields: Vec<&str> = line.splitn(4, '\t').collect();
+            let [day, part, fingerprint, answer] = fields[..] else {
+                return Err(source.error(line, "day, part, fingerprint and answer separated by tabs"));
+            };
+            let part = match part {
+                "1" => Part::One,
+                "2" => Part::Two,
+                _   => return Err(source.error(part, "a part (1 or 2)")),
+            };
+            let answer = unescape(answer).ok_or_else(|| source.error(answer, "an answer with only \\\\, \\t, \\n or \\r escapes"))?;
+            book.entries.insert((source.number(day)?, part, fingerprint.to_string()), answer);
+        }
+
+        Ok(book)
+    }
+
+    // A missing file is an empty book; anything else unreadable is an error.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match read_file(path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound
+                         => Ok(Self::default()),
+            Err(err)     => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string()).map_err(|source| Error::Io {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    pub fn get(&self, day: u8, part: Part, fingerprint: &str) -> Option<&str> {
+        self.entries.get(&(day, part, fingerprint.to_string())).map(|s| s.as_str())
+    }
+
+    pub fn check(&self, day: u8, part: Part, fingerprint: &str, answer: &Answer) -> Verdict {
+        match self.get(day, part, fingerprint) {
+            None                                             => Verdict::Unknown,
+            Some(expected) if expected == answer.to_string() => Verdict::Match,
+            Some(expected)                                   => Verdict::Mismatch { expected: expected.to_string() },
+        }
+    }
+
+    // Stores `answer`, returning the answer it replaced, if any.
+    pub fn record(&mut self, day: u8, part: Part, fingerprint: &str, answer: &Answer) -> Option<String> {
+        self.entries.insert((day, part, fingerprint.to_string()), answer.to_string())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl std::fmt::Display for AnswerBook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# day\tpart\tfingerprint\tanswer")?;
+        for ((day, part, fingerprint), answer) in &self.entries {
+            writeln!(f, "{}\t{}\t{}\t{}", day, part, fingerprint, escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_is_stable() {
+        assert_eq!(fingerprint(""), "cbf29ce484222325");
+        assert_eq!(fingerprint("a"), "af63dc4c8601ec8c");
+        assert_ne!(fingerprint("1 2 3\n"), fingerprint("1 2 4\n"));
+    }
+
+    #[test]
+    fn checks_and_records_answers() {
+        let mut book = AnswerBook::default();
+        let fp = fingerprint("input");
+
+        assert_eq!(book.check(7, Part::One, &fp, &6440.into()), Verdict::Unknown);
+        assert_eq!(book.record(7, Part::One, &fp, &6440.into()), None);
+        assert_eq!(book.check(7, Part::One, &fp, &6440.into()), Verdict::Match);
+        assert_eq!(book.check(7, Part::One, &fp, &6441.into()),
+                   Verdict::Mismatch { expected: "6440".to_string() });
+        assert_eq!(book.check(7, Part::Two, &fp, &6440.into()), Verdict::Unknown);
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let mut book = AnswerBook::default();
+        book.record(9, Part::Two, "00ff", &(-2).into());
+        book.record(1, Part::One, "abcd", &142.into());
+
+        let text = book.to_string();
+        assert_eq!(text, "# day\tpart\tfingerprint\tanswer\n1\t1\tabcd\t142\n9\t2\t00ff\t-2\n");
+        assert_eq!(AnswerBook::parse(&text).unwrap(), book);
+        assert!(AnswerBook::parse("1\t3\tabcd\t142\n").is_err());
+    }
+
+    #[test]
+    fn text_answers_are_escaped() {
+        let mut book = AnswerBook::default();
+        book.record(13, Part::One, "abcd", &"a\tb\nc\\d\r".to_string().into());
+
+        let text = book.to_string();
+        assert_eq!(text.lines().nth(1), Some("13\t1\tabcd\ta\\tb\\nc\\\\d\\r"));
+        assert_eq!(AnswerBook::parse(&text).unwrap(), book);
+        assert!(AnswerBook::parse("1\t1\tabcd\tx\\y\n").is_err());
+        assert!(AnswerBook::parse("1\t1\tabcd\tx\\\n").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::{Error, Result},
+    timing,
+};
+
+// Lets one thread ask solvers running on others to stop at their next step.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+// Which limit a solver ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exceeded {
+    Steps(u64),
+    Time(Duration),
+    Cancelled,
+}
+
+impl fmt::Display for Exceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exceeded::Steps(steps) => write!(f, "took more than {} steps", steps),
+            Exceeded::Time(limit)  => write!(f, "ran longer than {}", timing::format_duration(*limit)),
+            Exceeded::Cancelled    => write!(f, "cancelled"),
+        }
+    }
+}
+
+// Limits on a solver run: a number of steps, a running time and a
+// cancellation token, any of which may be absent. Solvers never see the
+// budget itself; they call `step` in their long-running loops, which charges
+// whatever budget the caller installed with `run`.
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    steps: Option<u64>,
+    time: Option<Duration>,
+    token: Option<CancelToken>,
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn steps(mut self, steps: u64) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    pub fn time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn token(mut self, token: CancelToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    // Runs `f` with this budget charged by every `step` it makes on this
+    // thread. The clock starts now. Within another `run`, the steps are
+    // charged to the outer budget too, and its limits still hold.
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        let outer = METER.with(RefCell::take);
+        let mut meter = Meter {
+            used: 0,
+            steps: self.steps.map(|steps| (steps, steps)),
+            deadline: self.time.map(|time| (Instant::now() + time, time)),
+            tokens: self.token.iter().cloned().collect(),
+        };
+        if let Some(outer) = &outer {
+            meter.within(outer);
+        }
+        METER.with(|current| *current.borrow_mut() = Some(meter));
+        let _restore = Restore(outer);
+        f()
+    }
+}
+
+struct Meter {
+    used: u64,
+    // The most steps this run may take, and the limit to report past them.
+    steps: Option<(u64, u64)>,
+    // When this run must stop, and the time limit to report past it.
+    deadline: Option<(Instant, Duration)>,
+    tokens: Vec<CancelToken>,
+}
+
+impl Meter {
+    // Caps this meter by what is left of `outer`, so that a nested run
+    // cannot outspend the one around it.
+    fn within(&mut self, outer: &Meter) {
+        if let Some((most, limit)) = outer.steps {
+            let left = (most.saturating_sub(outer.used), limit);
+            self.steps = Some(self.steps.filter(|own| own.0 <= left.0).unwrap_or(left));
+        }
+        if let Some(outer_deadline) = outer.deadline {
+            self.deadline = Some(self.deadline.filter(|own| own.0 <= outer_deadline.0).unwrap_or(outer_deadline));
+        }
+        self.tokens.extend(outer.tokens.iter().cloned());
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.tokens.iter().any(CancelToken::is_cancelled) {
+            return Err(Error::BudgetExceeded(Exceeded::Cancelled));
+        }
+        match self.deadline {
+            Some((deadline, time)) if Instant::now() >= deadline
+                  => Err(Error::BudgetExceeded(Exceeded::Time(time))),
+            _     => Ok(()),
+        }
+    }
+}
+
+thread_local! {
+    static METER: RefCell<Option<Meter>> = const { RefCell::new(None) };
+}
+
+// Puts back whatever budget was installed before `run`, even if `f` panics,
+// charged with the steps taken meanwhile.
+struct Restore(Option<Meter>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let mut previous = self.0.take();
+        METER.with(|current| {
+            let mut current = current.borrow_mut();
+            if let (Some(outer), Some(inner)) = (previous.as_mut(), current.as_ref()) {
+                outer.used = outer.used.saturating_add(inner.used);
+            }
+            *current = previous;
+        });
+    }
+}
+
+// The clock and the token are only consulted every this many steps, so that
+// a step stays cheap enough for the innermost loops.
+const CHECK_EVERY: u64 = 1024;
+
+pub fn step() -> Result<()> {
+    steps(1)
+}
+
+// Charges `n` steps to the current budget, failing once it is spent. Without
+// a budget installed this always succeeds.
+pub fn steps(n: u64) -> Result<()> {
+    METER.with(|current| {
+        let mut current = current.borrow_mut();
+        let Some(meter) = current.as_mut() else { return Ok(()) };
+
+        let before = meter.used;
+        meter.used = meter.used.saturating_add(n);
+        if let Some((_, limit)) = meter.steps.filter(|&(most, _)| meter.used > most) {
+            return Err(Error::BudgetExceeded(Exceeded::Steps(limit)));
+        }
+        if before / CHECK_EVERY != meter.used / CHECK_EVERY {
+            meter.check()?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Loops until the budget runs out.
+    fn spin() -> Result<()> {
+        loop {
+            step()?;
+        }
+    }
+
+    #[test]
+    fn step_limits_stop_loops() {
+        let err = Budget::unlimited().steps(10_000).run(spin).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(Exceeded::Steps(10_000))));
+        assert_eq!(err.to_string(), "budget exceeded: took more than 10000 steps");
+    }
+
+    #[test]
+    fn time_limits_and_tokens_stop_loops() {
+        let err = Budget::unlimited().time(Duration::from_millis(10)).run(spin).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(Exceeded::Time(_))));
+
+        let token = CancelToken::new();
+        token.cancel();
+        let err = Budget::unlimited().token(token).run(spin).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(Exceeded::Cancelled)));
+    }
+
+    #[test]
+    fn budgets_nest_and_unwind() {
+        assert!(step().is_ok());
+        Budget::unlimited().steps(100).run(|| {
+            assert!(Budget::unlimited().steps(1_000).run(|| steps(60)).is_ok());
+            assert!(steps(30).is_ok());
+            assert!(steps(20).is_err());
+        });
+        assert!(steps(u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn nested_budgets_keep_the_outer_limits() {
+        // An unlimited inner budget still runs out with the outer one.
+        let err = Budget::unlimited().steps(10_000).run(|| Budget::unlimited().run(spin)).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(Exceeded::Steps(10_000))));
+        let err = Budget::unlimited().time(Duration::from_millis(10)).run(|| Budget::unlimited().steps(u64::MAX).run(spin)).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(Exceeded::Time(_))));
+
+        let token = CancelToken::new();
+        token.cancel();
+        let err = Budget::unlimited().token(token).run(|| Budget::unlimited().run(spin)).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(Exceeded::Cancelled)));
+
+        // A tighter inner limit is reported as its own.
+        let err = Budget::unlimited().steps(10_000).run(|| Budget::unlimited().steps(10).run(spin)).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(Exceeded::Steps(10))));
+    }
+}
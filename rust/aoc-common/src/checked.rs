@@ -0,0 +1,63 @@
+use crate::error::{Error, Result};
+
+// The error for answers, or the numbers on the way to them, too large for the
+// integers a solver counts in.
+pub fn overflow() -> Error {
+    Error::Overflow
+}
+
+// Integers that can be added and multiplied without wrapping.
+pub trait Checked: Copy {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn add(self, other: Self) -> Option<Self>;
+    fn mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! checked_int {
+    ($($t:ty),*) => {
+        $(impl Checked for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn add(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+
+            fn mul(self, other: Self) -> Option<Self> {
+                self.checked_mul(other)
+            }
+        })*
+    };
+}
+
+checked_int!(u32, u64, usize, i32, i64, isize);
+
+pub fn sum<T: Checked>(values: impl IntoIterator<Item = T>) -> Result<T> {
+    values.into_iter().try_fold(T::ZERO, |total, value| total.add(value)).ok_or_else(overflow)
+}
+
+pub fn product<T: Checked>(values: impl IntoIterator<Item = T>) -> Result<T> {
+    values.into_iter().try_fold(T::ONE, |total, value| total.mul(value)).ok_or_else(overflow)
+}
+
+// Like `sum`, over values that may have failed to come about; the first
+// error wins.
+pub fn try_sum<T: Checked>(values: impl IntoIterator<Item = Result<T>>) -> Result<T> {
+    values.into_iter().try_fold(T::ZERO, |total, value| total.add(value?).ok_or_else(overflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_is_an_error() {
+        assert_eq!(sum([1usize, 2, 3]).unwrap(), 6);
+        assert!(matches!(sum([usize::MAX, 1]), Err(Error::Overflow)));
+        assert_eq!(product([-2isize, 3]).unwrap(), -6);
+        assert!(product([isize::MAX, 2]).is_err());
+        assert!(matches!(try_sum([Ok(1usize), Err(Error::NotImplemented), Ok(2)]), Err(Error::NotImplemented)));
+    }
+}
@@ -0,0 +1,39 @@
+use std::{fmt, io};
+
+use crate::{budget::Exceeded, parse::ParseError};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io { path: String, source: io::Error },
+    Parse(ParseError),
+    Unsolvable(String),
+    // The answer, or a number on the way to it, does not fit the integers the
+    // solver counts in; the input may well have an answer.
+    Overflow,
+    BudgetExceeded(Exceeded),
+    NotImplemented,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "failed to read {}: {}", path, source),
+            Error::Parse(err)          => write!(f, "parse error: {}", err),
+            Error::Unsolvable(msg)     => write!(f, "no solution: {}", msg),
+            Error::Overflow            => write!(f, "overflow: the numbers are too large"),
+            Error::BudgetExceeded(how) => write!(f, "budget exceeded: {}", how),
+            Error::NotImplemented      => write!(f, "not done yet"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _                        => None,
+        }
+    }
+}
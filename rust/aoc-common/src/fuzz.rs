@@ -0,0 +1,129 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
+
+use crate::{
+    budget::Budget,
+    generate::Rng,
+    solution::{Part, Solver},
+};
+
+// What one fuzz input may spend on each part, so that a brute force handed
+// huge numbers ends in an error instead of running for hours.
+pub const STEPS: u64 = 1 << 20;
+pub const TIME: Duration = Duration::from_secs(1);
+
+// Fragments that mean something to one parser or another, spliced into the
+// inputs so that mutations get past the first check more often.
+const TOKENS: &[&str] = &[
+    "\n", "\n\n", " ", ": ", ", ", "; ", " | ", " = (", ")", "-", "0", "1", "7", "9", "-1",
+    "4294967296", "18446744073709551615", "99999999999999999999999",
+    "S", ".", "*", "#", "|", "F", "J", "L", "7", "A", "Z", "AAA", "R", "T",
+    "Game 1", "Card 1", "seeds:", "map:", "Time:", "Distance:", "red", "one", "eight",
+];
+
+// The message a panic was raised with.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown cause".to_string())
+}
+
+// The fuzz target of every day: parses `data` and solves both parts from it,
+// each within the fuzzing budget. Any error is a fine answer to garbage; only
+// a panic is a bug, returned as its message.
+pub fn target(solver: &dyn Solver, data: &[u8]) -> std::result::Result<(), String> {
+    let contents = String::from_utf8_lossy(data);
+    let budget = Budget::unlimited().steps(STEPS).time(TIME);
+
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        if let Ok(input) = budget.run(|| solver.parse(&contents)) {
+            for part in Part::ALL {
+                let _ = budget.run(|| solver.solve(part, input.as_ref()));
+            }
+        }
+    }))
+    .map_err(|payload| panic_message(payload.as_ref()))
+}
+
+// A few random edits of `input`: inserted tokens, removed, overwritten or
+// repeated spans, pieces of another `corpus` entry and truncation.
+pub fn mutate(rng: &mut Rng, input: &[u8], corpus: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = input.to_vec();
+    for _ in 0..rng.range(1..5) {
+        let at = rng.range(0..data.len() + 1);
+        let end = (at + rng.range(1..16)).min(data.len());
+        match rng.range(0..7) {
+            0 => {
+                let token = rng.pick(TOKENS).as_bytes();
+                data.splice(at..at, token.iter().copied());
+            },
+            1 => {
+                data.drain(at..end);
+            },
+            2 => if at < data.len() {
+                data[at] = rng.range(0..128) as u8;
+            },
+            3 => {
+                let span = data[at..end].to_vec();
+                let to = rng.range(0..data.len() + 1);
+                data.splice(to..to, span);
+            },
+            4 => if !corpus.is_empty() {
+                let other = rng.pick(corpus);
+                let from = rng.range(0..other.len() + 1);
+                let span = &other[from..(from + rng.range(1..64)).min(other.len())];
+                data.splice(at..at, span.iter().copied());
+            },
+            5 => if at < data.len() && data[at].is_ascii_digit() {
+                // Grow a number, to reach the overflows.
+                let digits: Vec<u8> = (0..rng.range(1..20)).map(|_| b'0' + rng.range(0..10) as u8).collect();
+                data.splice(at..at, digits);
+            },
+            _ => data.truncate(at),
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, Error, Result, Solution};
+
+    struct Fragile;
+
+    impl Solution for Fragile {
+        type Input = Vec<usize>;
+
+        fn parse(&self, contents: &str) -> Result<Self::Input> {
+            contents.split_whitespace()
+                .map(|word| word.parse().map_err(|_| Error::Unsolvable(format!("not a number: {}", word))))
+                .collect()
+        }
+
+        fn part_1(&self, input: &Self::Input) -> Result<Answer> {
+            Ok(input[2].into())
+        }
+    }
+
+    #[test]
+    fn only_panics_are_failures() {
+        assert_eq!(target(&Fragile, b"1 2 3"), Ok(()));
+        assert_eq!(target(&Fragile, b"1 x"), Ok(()));
+        assert!(target(&Fragile, b"1 2").unwrap_err().contains("index out of bounds"));
+    }
+
+    #[test]
+    fn mutations_repeat_for_a_seed() {
+        let corpus = vec![b"Game 1: 3 blue, 4 red".to_vec()];
+        let mutants = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..20).map(|_| mutate(&mut rng, &corpus[0], &corpus)).collect::<Vec<_>>()
+        };
+        assert_eq!(mutants(3), mutants(3));
+        assert!(mutants(3).iter().any(|mutant| *mutant != corpus[0]));
+    }
+}
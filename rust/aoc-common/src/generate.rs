@@ -0,0 +1,113 @@
+use std::ops::Range;
+
+use crate::solution::{Answer, Part, Solver};
+
+// A small deterministic generator (SplitMix64), so that a seed produces the
+// same input on every platform and Rust version without pulling in a crate.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "cannot pick from an empty range");
+        let span = (range.end - range.start) as u128;
+        range.start + ((self.next_u64() as u128 * span) >> 64) as usize
+    }
+
+    // True with probability `numerator / denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.range(0..denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i + 1));
+        }
+    }
+}
+
+// A synthetic puzzle input and, where the generator can tell without solving
+// it, the answers it should produce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub answers: [Option<Answer>; 2],
+}
+
+impl Generated {
+    pub fn new(input: String, part_1: impl Into<Answer>, part_2: impl Into<Answer>) -> Self {
+        Self { input, answers: [Some(part_1.into()), Some(part_2.into())] }
+    }
+
+    pub fn answer(&self, part: Part) -> Option<&Answer> {
+        self.answers[part.number() as usize - 1].as_ref()
+    }
+}
+
+// Every day's generator: a seed and a size, whose meaning (lines, games,
+// nodes...) each day documents, to a well-formed input.
+pub type Generator = fn(seed: u64, size: usize) -> Generated;
+
+// Panics unless `solver`, and each of its implementations, finds every answer
+// `generated` knows, showing the input on failure; for the days' generator tests.
+pub fn assert_solves(solver: &dyn Solver, generated: &Generated) {
+    let input = solver.parse(&generated.input)
+        .unwrap_or_else(|err| panic!("{}\nin generated input:\n{}", err, generated.input));
+    for part in Part::ALL {
+        if let Some(expected) = generated.answer(part) {
+            let answer = solver.solve(part, input.as_ref());
+            assert_eq!(answer.as_ref().ok(), Some(expected), "part {} of generated input:\n{}", part, generated.input);
+            for name in solver.implementations(part) {
+                let answer = solver.solve_with(part, name, input.as_ref());
+                assert_eq!(answer.as_ref().ok(), Some(expected), "part {} ({}) of generated input:\n{}", part, name, generated.input);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_repeat() {
+        let draws = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.range(0..1000)).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(7), draws(7));
+        assert_ne!(draws(7), draws(8));
+    }
+
+    #[test]
+    fn draws_stay_in_range() {
+        let mut rng = Rng::new(1);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            seen[rng.range(3..9) - 3] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+}
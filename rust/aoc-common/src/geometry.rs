@@ -0,0 +1,174 @@
+use crate::{checked::overflow, error::Result, math::gcd_u128};
+
+// A lattice point, `(x, y)`. Grid positions are `(row, column)`, so they map
+// to `(column, row)`, with y pointing down the grid.
+pub type Point = (i64, i64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Counterclockwise,
+    Clockwise,
+    // Collinear points, or a polygon with no area.
+    Degenerate,
+}
+
+impl Orientation {
+    fn of(value: i128) -> Self {
+        match value.signum() {
+            1  => Orientation::Counterclockwise,
+            -1 => Orientation::Clockwise,
+            _  => Orientation::Degenerate,
+        }
+    }
+}
+
+// The edges of the closed polygon through `vertices`, the last back to the
+// first.
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices.iter().zip(vertices.iter().cycle().skip(1)).map(|(&a, &b)| (a, b))
+}
+
+// `a * d - b * c` in i128, an error if it does not fit.
+fn cross(a: i128, b: i128, c: i128, d: i128) -> Result<i128> {
+    a.checked_mul(d).zip(b.checked_mul(c))
+        .and_then(|(ad, bc)| ad.checked_sub(bc))
+        .ok_or_else(overflow)
+}
+
+// Twice the signed area of the polygon through `vertices` in order, by the
+// shoelace formula: positive if they run counterclockwise with y up. Twice,
+// so that it stays an integer. Exact in i128, which holds it for coordinates
+// within ±2^61; an error for a polygon too large for it.
+pub fn twice_signed_area(vertices: &[Point]) -> Result<i128> {
+    edges(vertices).try_fold(0_i128, |sum, ((x1, y1), (x2, y2))| {
+        sum.checked_add(cross(x1 as i128, x2 as i128, y1 as i128, y2 as i128)?).ok_or_else(overflow)
+    })
+}
+
+pub fn twice_area(vertices: &[Point]) -> Result<u128> {
+    twice_signed_area(vertices).map(i128::unsigned_abs)
+}
+
+// Which way the polygon through `vertices` runs, with y up.
+pub fn orientation(vertices: &[Point]) -> Result<Orientation> {
+    twice_signed_area(vertices).map(Orientation::of)
+}
+
+// Which way the path from `a` through `b` turns at `b` to reach `c`; an
+// error if the points are too far apart to tell exactly.
+pub fn turn(a: Point, b: Point, c: Point) -> Result<Orientation> {
+    let (ux, uy) = (b.0 as i128 - a.0 as i128, b.1 as i128 - a.1 as i128);
+    let (vx, vy) = (c.0 as i128 - b.0 as i128, c.1 as i128 - b.1 as i128);
+    cross(ux, uy, vx, vy).map(Orientation::of)
+}
+
+// The lattice points on the polygon's edges. Only its corners need be
+// listed; for a polygon along the grid lines this is also its perimeter.
+pub fn boundary_points(vertices: &[Point]) -> u128 {
+    edges(vertices)
+        .map(|((x1, y1), (x2, y2))| gcd_u128(x1.abs_diff(x2) as u128, y1.abs_diff(y2) as u128))
+        .sum()
+}
+
+// The lattice points strictly inside a simple polygon, by Pick's theorem:
+// `area = interior + boundary / 2 - 1`. A polygon with no area has nothing
+// inside; a self-crossing one may give any number, negative even.
+pub fn interior_points(vertices: &[Point]) -> Result<i128> {
+    let twice_area = twice_signed_area(vertices)?.checked_abs().ok_or_else(overflow)?;
+    if twice_area == 0 {
+        return Ok(0);
+    }
+    let boundary = i128::try_from(boundary_points(vertices)).map_err(|_| overflow())?;
+    Ok((twice_area - boundary + 2) / 2)
+}
+
+// How many times the polygon winds counterclockwise around `point`, which
+// must not lie on it: 0 outside a simple polygon, ±1 inside.
+pub fn winding_number(vertices: &[Point], point: Point) -> Result<i64> {
+    edges(vertices).try_fold(0, |winding, (a, b)| {
+        Ok(winding + match (a.1 <= point.1, b.1 <= point.1) {
+            (true, false) if turn(a, b, point)? == Orientation::Counterclockwise => 1,
+            (false, true) if turn(a, b, point)? == Orientation::Clockwise        => -1,
+            _                                                                    => 0,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    // A 4x3 rectangle by its corners, and by every lattice point around it.
+    const CORNERS: [Point; 4] = [(0, 0), (4, 0), (4, 3), (0, 3)];
+
+    fn every_point() -> Vec<Point> {
+        let mut points: Vec<Point> = (0..4).map(|x| (x, 0)).collect();
+        points.extend((0..3).map(|y| (4, y)));
+        points.extend((1..=4).rev().map(|x| (x, 3)));
+        points.extend((1..=3).rev().map(|y| (0, y)));
+        points
+    }
+
+    #[test]
+    fn corners_and_every_point_agree() {
+        for polygon in [CORNERS.to_vec(), every_point()] {
+            assert_eq!(twice_signed_area(&polygon).unwrap(), 24);
+            assert_eq!(boundary_points(&polygon), 14);
+            assert_eq!(interior_points(&polygon).unwrap(), 3 * 2);
+            assert_eq!(orientation(&polygon).unwrap(), Orientation::Counterclockwise);
+        }
+        let reversed: Vec<Point> = CORNERS.iter().rev().copied().collect();
+        assert_eq!(twice_signed_area(&reversed).unwrap(), -24);
+        assert_eq!(orientation(&reversed).unwrap(), Orientation::Clockwise);
+    }
+
+    #[test]
+    fn diagonal_edges_count_their_lattice_points() {
+        let triangle = [(0, 0), (6, 0), (0, 4)];
+        assert_eq!(twice_area(&triangle).unwrap(), 24);
+        assert_eq!(boundary_points(&triangle), 6 + 2 + 4);
+        // 12 = I + 12 / 2 - 1
+        assert_eq!(interior_points(&triangle).unwrap(), 7);
+    }
+
+    #[test]
+    fn large_polygons_stay_exact() {
+        let side = 1_000_000_007_i64;
+        let square = [(-side, -side), (side, -side), (side, side), (-side, side)];
+        assert_eq!(twice_area(&square).unwrap(), 2 * (2 * side as u128).pow(2));
+        assert_eq!(interior_points(&square).unwrap(), (2 * side as i128 - 1).pow(2));
+
+        // At the ends of i64, twice the area is about 2^129.
+        let (low, high) = (i64::MIN, i64::MAX);
+        let square = [(low, low), (high, low), (high, high), (low, high)];
+        assert!(matches!(twice_signed_area(&square), Err(Error::Overflow)));
+        assert!(matches!(interior_points(&square), Err(Error::Overflow)));
+        assert!(matches!(turn((low, low), (high, low), (high, high)), Err(Error::Overflow)));
+        let sliver = [(low, 0), (high, 0), (high, 1)];
+        assert_eq!(twice_area(&sliver).unwrap(), u64::MAX as u128);
+    }
+
+    #[test]
+    fn degenerate_polygons_enclose_nothing() {
+        for polygon in [vec![], vec![(3, 4)], vec![(0, 0), (5, 5)], vec![(0, 0), (1, 1), (2, 2)]] {
+            assert_eq!(twice_area(&polygon).unwrap(), 0);
+            assert_eq!(interior_points(&polygon).unwrap(), 0);
+            assert_eq!(orientation(&polygon).unwrap(), Orientation::Degenerate);
+        }
+        assert_eq!(turn((0, 0), (1, 0), (2, 0)).unwrap(), Orientation::Degenerate);
+        assert_eq!(turn((0, 0), (1, 0), (1, 1)).unwrap(), Orientation::Counterclockwise);
+    }
+
+    #[test]
+    fn winding_numbers() {
+        assert_eq!(winding_number(&CORNERS, (1, 1)).unwrap(), 1);
+        assert_eq!(winding_number(&CORNERS.iter().rev().copied().collect::<Vec<_>>(), (1, 1)).unwrap(), -1);
+        assert_eq!(winding_number(&CORNERS, (5, 1)).unwrap(), 0);
+        assert_eq!(winding_number(&CORNERS, (2, -1)).unwrap(), 0);
+        // A bow tie winds around its two halves in opposite directions.
+        let bow_tie = [(0, 0), (4, 4), (4, 0), (0, 4)];
+        assert_eq!(winding_number(&bow_tie, (1, 2)).unwrap(), 1);
+        assert_eq!(winding_number(&bow_tie, (3, 2)).unwrap(), -1);
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::{Display, Write},
+    hash::Hash,
+};
+
+use crate::{budget, error::Result};
+
+// Nodes are numbered from 0 in the order they are added.
+pub type NodeId = usize;
+
+// A directed graph with a label on every node and a weight on every edge,
+// kept as a list of outgoing edges per node. An undirected graph adds each
+// edge both ways.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    edges: Vec<Vec<(NodeId, u64)>>,
+}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Graph { nodes: Vec::new(), edges: Vec::new() }
+    }
+}
+
+// What a breadth- or depth-first search reached from its start: the nodes in
+// the order it visited them, and for each node how many edges the search
+// took to it and from where, `None` for nodes it never reached.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Search {
+    pub order: Vec<NodeId>,
+    pub distance: Vec<Option<usize>>,
+    pub parent: Vec<Option<NodeId>>,
+}
+
+impl Search {
+    // The nodes from the start to `node` along the search's edges.
+    pub fn path_to(&self, node: NodeId) -> Option<Vec<NodeId>> {
+        self.distance[node]?;
+        let mut path = vec![node];
+        while let Some(parent) = self.parent[*path.last().unwrap()] {
+            path.push(parent);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<N> Graph<N> {
+    pub fn new() -> Self {
+        Graph::default()
+    }
+
+    pub fn add_node(&mut self, label: N) -> NodeId {
+        self.nodes.push(label);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: NodeId, to: NodeId, weight: u64) {
+        assert!(to < self.nodes.len(), "no node {} in a graph of {}", to, self.nodes.len());
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> {
+        self.nodes.iter().enumerate()
+    }
+
+    // The first node labelled `label`.
+    pub fn find(&self, label: &N) -> Option<NodeId>
+    where
+        N: PartialEq,
+    {
+        self.nodes.iter().position(|node| node == label)
+    }
+
+    // The edges out of `id` and their weights, in the order they were added.
+    pub fn edges(&self, id: NodeId) -> &[(NodeId, u64)] {
+        &self.edges[id]
+    }
+
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[id].iter().map(|&(to, _)| to)
+    }
+
+    fn search(&self) -> Search {
+        Search { order: Vec::new(), distance: vec![None; self.len()], parent: vec![None; self.len()] }
+    }
+
+    // Every node reachable from `start`, nearest first, with the fewest edges
+    // to each.
+    pub fn bfs(&self, start: NodeId) -> Search {
+        let mut search = self.search();
+        let mut queue = VecDeque::from([start]);
+        search.distance[start] = Some(0);
+
+        while let Some(node) = queue.pop_front() {
+            search.order.push(node);
+            for next in self.neighbors(node) {
+                if search.distance[next].is_none() {
+                    search.distance[next] = search.distance[node].map(|distance| distance + 1);
+                    search.parent[next] = Some(node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        search
+    }
+
+    // Every node reachable from `start`, each edge followed as deep as it
+    // goes before the next, as a recursive search would; distances are along
+    // the search's own path.
+    pub fn dfs(&self, start: NodeId) -> Search {
+        let mut search = self.search();
+        let mut stack: Vec<(NodeId, Option<NodeId>)> = vec![(start, None)];
+
+        while let Some((node, parent)) = stack.pop() {
+            if search.distance[node].is_some() {
+                continue;
+            }
+            search.distance[node] = Some(parent.map_or(0, |parent| search.distance[parent].unwrap() + 1));
+            search.parent[node] = parent;
+            search.order.push(node);
+            // Reversed, so that the first edge comes off the stack first.
+            stack.extend(self.neighbors(node).collect::<Vec<_>>().into_iter().rev()
+                .filter(|&next| search.distance[next].is_none())
+                .map(|next| (next, Some(node))));
+        }
+        search
+    }
+
+    // The nodes in groups joined by edges, whichever way those run: each
+    // group in order, the groups by their first node.
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut undirected: Graph<()> = Graph { nodes: vec![(); self.len()], edges: vec![Vec::new(); self.len()] };
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, _) in edges {
+                undirected.add_edge(from, to);
+                undirected.add_edge(to, from);
+            }
+        }
+
+        let mut seen = vec![false; self.len()];
+        let mut components = Vec::new();
+        for node in 0..self.len() {
+            if seen[node] {
+                continue;
+            }
+            let mut component = undirected.bfs(node).order;
+            component.sort();
+            component.iter().for_each(|&node| seen[node] = true);
+            components.push(component);
+        }
+        components
+    }
+
+    // The cheapest path from `from` to `to` by the edges' weights, and its
+    // cost.
+    pub fn shortest_path(&self, from: NodeId, to: NodeId) -> Option<(u64, Vec<NodeId>)> {
+        dijkstra(from, |&node| self.edges(node).to_vec(), |&node| node == to)
+    }
+
+    // The graph in Graphviz's DOT language, nodes labelled with their labels
+    // and edges with their weights unless those are all 1.
+    pub fn to_dot(&self) -> String
+    where
+        N: Display,
+    {
+        let weighted = self.edges.iter().flatten().any(|&(_, weight)| weight != 1);
+        let mut dot = "digraph {\n".to_string();
+        for (id, label) in self.nodes() {
+            writeln!(dot, "    {} [label=\"{}\"];", id, dot_escape(&label.to_string())).unwrap();
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, weight) in edges {
+                match weighted {
+                    true  => writeln!(dot, "    {} -> {} [label=\"{}\"];", from, to, weight).unwrap(),
+                    false => writeln!(dot, "    {} -> {};", from, to).unwrap(),
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// `label` as the inside of a DOT string, where only `"` and `\` are escaped;
+// everything else, non-ASCII letters included, stands for itself.
+fn dot_escape(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        if c == '"' || c == '\\' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// The cheapest way from `start` to a state meeting `goal`, stepping from each
+// state to its `successors` at the cost given with each: the total cost and
+// the states along the way. The states may be anything hashable, so the
+// graph need not be built first.
+pub fn dijkstra<S, I>(start: S, successors: impl FnMut(&S) -> I, goal: impl FnMut(&S) -> bool) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    astar(start, successors, |_| 0, goal)
+}
+
+// `dijkstra` guided by a `heuristic` estimate of the cost left from a state.
+// The path found is the cheapest as long as the estimate never exceeds the
+// true cost.
+pub fn astar<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u64,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    // States are numbered as they are met, so the heap holds plain numbers.
+    let mut states: Vec<S> = vec![start.clone()];
+    let mut index: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut cost: Vec<u64> = vec![0];
+    let mut parent: Vec<Option<usize>> = vec![None];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+
+    while let Some(Reverse((_, spent, current))) = heap.pop() {
+        if spent > cost[current] {
+            continue;
+        }
+        if goal(&states[current]) {
+            let mut path = vec![current];
+            while let Some(previous) = parent[*path.last().unwrap()] {
+                path.push(previous);
+            }
+            return Some((spent, path.into_iter().rev().map(|i| states[i].clone()).collect()));
+        }
+
+        for (next, step) in successors(&states[current]) {
+            let Some(total) = spent.checked_add(step) else { continue };
+            let i = *index.entry(next.clone()).or_insert_with(|| {
+                states.push(next.clone());
+                cost.push(u64::MAX);
+                parent.push(None);
+                states.len() - 1
+            });
+            if total < cost[i] {
+                cost[i] = total;
+                parent[i] = Some(current);
+                heap.push(Reverse((total.saturating_add(heuristic(&next)), total, i)));
+            }
+        }
+    }
+    None
+}
+
+// Where a walk through a functional graph, one where every state leads to
+// exactly one next, ends up going round: after `tail` steps it enters a loop
+// of `length` states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub length: usize,
+}
+
+// The loop that following `next` from `start` falls into, found by Brent's
+// method without storing the states it passes. Each step is charged to the
+// caller's budget, as the loop may be long.
+pub fn find_cycle<S: Clone + PartialEq>(start: S, mut next: impl FnMut(&S) -> S) -> Result<Cycle> {
+    let mut step = |state: &S| budget::step().map(|()| next(state));
+
+    // The length: the hare runs ahead of a tortoise that jumps to it at each
+    // power of two, until it catches the tortoise up.
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = start.clone();
+    let mut hare = step(&start)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    // The tail: two walkers `length` apart meet where the loop begins.
+    let (mut tortoise, mut hare) = (start.clone(), start);
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        tail += 1;
+    }
+    Ok(Cycle { tail, length })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a → b → c → d, a → c, and e → f apart from them.
+    fn graph() -> Graph<char> {
+        let mut graph = Graph::new();
+        for label in "abcdef".chars() {
+            graph.add_node(label);
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (0, 2), (4, 5)] {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    #[test]
+    fn searches_find_distances_and_paths() {
+        let graph = graph();
+        let bfs = graph.bfs(0);
+        assert_eq!(bfs.order, [0, 1, 2, 3]);
+        assert_eq!(bfs.distance, [Some(0), Some(1), Some(1), Some(2), None, None]);
+        assert_eq!(bfs.path_to(3), Some(vec![0, 2, 3]));
+        assert_eq!(bfs.path_to(5), None);
+
+        let dfs = graph.dfs(0);
+        assert_eq!(dfs.order, [0, 1, 2, 3]);
+        assert_eq!(dfs.distance[3], Some(3));
+        assert_eq!(dfs.path_to(3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(graph.find(&'e').map(|e| graph.bfs(e).order), Some(vec![4, 5]));
+    }
+
+    #[test]
+    fn components_ignore_direction() {
+        let mut graph = graph();
+        graph.add_node('g');
+        assert_eq!(graph.components(), [vec![0, 1, 2, 3], vec![4, 5], vec![6]]);
+    }
+
+    #[test]
+    fn shortest_paths_follow_weights() {
+        let mut graph = graph();
+        graph.add_weighted_edge(0, 3, 5);
+        graph.add_weighted_edge(1, 3, 1);
+        assert_eq!(graph.shortest_path(0, 3), Some((2, vec![0, 1, 3])));
+        assert_eq!(graph.shortest_path(3, 0), None);
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra() {
+        // Across a 20x20 grid whose cells cost 1 to 9 to enter.
+        let cost = |(x, y): (i64, i64)| (x * 7 + y * 13 + x * y) % 9 + 1;
+        let successors = |&(x, y): &(i64, i64)| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)].into_iter()
+                .filter(|&(x, y)| (0..20).contains(&x) && (0..20).contains(&y))
+                .map(move |next| (next, cost(next) as u64))
+        };
+        let goal = |&state: &(i64, i64)| state == (19, 19);
+        let manhattan = |&(x, y): &(i64, i64)| (38 - x - y) as u64;
+
+        let (best, path) = dijkstra((0, 0), successors, goal).unwrap();
+        assert_eq!(astar((0, 0), successors, manhattan, goal).map(|(cost, _)| cost), Some(best));
+        assert_eq!(path.iter().skip(1).map(|&cell| cost(cell) as u64).sum::<u64>(), best);
+        assert_eq!((path.first(), path.last()), (Some(&(0, 0)), Some(&(19, 19))));
+    }
+
+    #[test]
+    fn cycles_match_brute_force() {
+        for modulus in 1..200_u64 {
+            let next = |&x: &u64| (x * x + 1) % modulus;
+            let mut seen = HashMap::new();
+            let mut state = 3 % modulus;
+            while !seen.contains_key(&state) {
+                seen.insert(state, seen.len());
+                state = next(&state);
+            }
+            let expected = Cycle { tail: seen[&state], length: seen.len() - seen[&state] };
+            assert_eq!(find_cycle(3 % modulus, next).unwrap(), expected, "mod {}", modulus);
+        }
+    }
+
+    #[test]
+    fn exports_dot() {
+        let mut graph: Graph<&str> = Graph::new();
+        let (a, b) = (graph.add_node("AAA"), graph.add_node("say \"hi\""));
+        graph.add_edge(a, b);
+        graph.add_edge(b, a);
+        assert_eq!(graph.to_dot(), "digraph {\n    0 [label=\"AAA\"];\n    1 [label=\"say \\\"hi\\\"\"];\n    0 -> 1;\n    1 -> 0;\n}\n");
+
+        graph.add_weighted_edge(a, a, 3);
+        assert!(graph.to_dot().contains("    0 -> 0 [label=\"3\"];\n"));
+    }
+
+    #[test]
+    fn dot_labels_escape_only_quotes_and_backslashes() {
+        let mut graph: Graph<&str> = Graph::new();
+        graph.add_node("café \"au\" lait");
+        graph.add_node("a\\b's");
+        assert_eq!(graph.to_dot(), "digraph {\n    0 [label=\"café \\\"au\\\" lait\"];\n    1 [label=\"a\\\\b's\"];\n}\n");
+    }
+}
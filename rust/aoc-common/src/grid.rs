@@ -0,0 +1,198 @@
+use std::{fmt, ops::{Index, IndexMut}};
+
+use crate::{error::Result, parse::Source};
+
+// A cell's row and column, counted from the top left.
+pub type Pos = (usize, usize);
+
+// The four steps to a cell's side neighbours, clockwise from north.
+pub const SIDES: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+// The eight steps to every neighbour, clockwise from north.
+pub const AROUND: [(isize, isize); 8] = [(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)];
+
+// A rectangle of cells stored row by row. Indexing with a `Pos` panics
+// outside the grid like a slice does; `get` and the neighbour iterators never
+// leave it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+}
+
+impl<T> Grid<T> {
+    // A grid of `height` rows of `width` copies of `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid { cells: vec![fill; width * height], width }
+    }
+
+    // The grid of `rows`, or `None` unless they are all as long as the first.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid { cells: rows.into_iter().flatten().collect(), width })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn contains(&self, (y, x): Pos) -> bool {
+        y < self.height() && x < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        match self.contains(pos) {
+            true  => Some(&mut self.cells[pos.0 * self.width + pos.1]),
+            false => None,
+        }
+    }
+
+    // The cell `step` away from `pos`, if that is still on the grid.
+    pub fn step(&self, (y, x): Pos, (dy, dx): (isize, isize)) -> Option<Pos> {
+        let pos = (y.checked_add_signed(dy)?, x.checked_add_signed(dx)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    // The side neighbours of `pos` on the grid, clockwise from north.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        SIDES.iter().filter_map(move |&step| self.step(pos, step))
+    }
+
+    // Every neighbour of `pos` on the grid, diagonals included, clockwise
+    // from north.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        AROUND.iter().filter_map(move |&step| self.step(pos, step))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `max` keeps `chunks` happy with an empty grid, which has no rows.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height()).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(if x < self.width { self.height() } else { 0 })
+    }
+
+    // Every cell with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (Pos, &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, cell)| ((i / width, i % width), cell))
+    }
+
+    // The position of the first cell equal to `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.cells.iter().position(|cell| cell == value).map(|i| (i / self.width, i % self.width))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(f).collect(), width: self.width }
+    }
+}
+
+impl Grid<char> {
+    // A grid with a cell per character of each line, every line as long as
+    // the first.
+    pub fn parse(source: &Source) -> Result<Self> {
+        let mut rows: Vec<Vec<char>> = Vec::new();
+        for line in source.lines() {
+            let row: Vec<char> = line.chars().collect();
+            if let Some(width) = rows.first().map(|first| first.len()) {
+                if row.len() != width {
+                    return Err(source.error(line, format!("a row of {} cells", width)));
+                }
+            }
+            rows.push(row);
+        }
+        Ok(Grid::from_rows(rows).expect("rows are checked as they are read"))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, self.width, self.height()))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height());
+        self.get_mut(pos).unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, width, height))
+    }
+}
+
+// The cells row by row, a line each, as they would be parsed back.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    fn grid() -> Grid<char> {
+        Grid::parse(&Source::new("ab.\n.S#\n")).unwrap()
+    }
+
+    #[test]
+    fn parses_and_displays() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.find(&'S'), Some((1, 1)));
+        assert_eq!(grid[(0, 1)], 'b');
+        assert_eq!(grid.to_string(), "ab.\n.S#\n");
+        assert_eq!(grid.row(1), Some(&['.', 'S', '#'][..]));
+        assert_eq!(grid.column(2).collect::<String>(), ".#");
+        assert_eq!(grid.cells().filter(|(_, &c)| c == '.').map(|(pos, _)| pos).collect::<Vec<_>>(), [(0, 2), (1, 0)]);
+    }
+
+    #[test]
+    fn ragged_rows_are_located() {
+        match Grid::parse(&Source::new("abc\nde\n")) {
+            Err(Error::Parse(err)) => assert_eq!((err.line, err.column, err.expected.as_str()), (2, 1, "a row of 3 cells")),
+            other                  => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(Grid::parse(&Source::new("")).unwrap().height(), 0);
+    }
+
+    #[test]
+    fn nothing_leaves_the_grid() {
+        let grid = grid();
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.neighbors4((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors8((0, 2)).collect::<Vec<_>>(), [(1, 2), (1, 1), (0, 1)]);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 5);
+        assert_eq!(grid.column(3).count(), 0);
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    env, fmt,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+// Directory holding `dayNN.txt` puzzle inputs, unless overridden.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_INPUT_DIR: &str = "inputs";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Path(PathBuf),
+    Stdin,
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Path(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin      => write!(f, "<stdin>"),
+        }
+    }
+}
+
+impl InputSource {
+    pub fn read(&self) -> Result<String> {
+        self.read_normalized().map(|(contents, _)| contents)
+    }
+
+    // The normalized input along with what normalizing it changed.
+    pub fn read_normalized(&self) -> Result<(String, Normalization)> {
+        let mut contents = String::new();
+        match self {
+            InputSource::Path(path) => File::open(path).and_then(|mut file| file.read_to_string(&mut contents)),
+            InputSource::Stdin      => std::io::stdin().read_to_string(&mut contents),
+        }.map_err(|source| Error::Io { path: self.to_string(), source })?;
+        Ok(normalize(&contents))
+    }
+
+    // A buffered reader over the input, for solving it without loading it whole.
+    pub fn open(&self) -> Result<Box<dyn BufRead>> {
+        match self {
+            InputSource::Path(path) => File::open(path)
+                .map(|file| Box::new(BufReader::new(file)) as Box<dyn BufRead>)
+                .map_err(|source| Error::Io { path: self.to_string(), source }),
+            InputSource::Stdin      => Ok(Box::new(std::io::stdin().lock())),
+        }
+    }
+}
+
+// What `normalize` had to change to bring an input into its canonical form.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    pub bom: bool,
+    pub line_endings: usize,
+    pub trailing_blank_lines: usize,
+    pub final_newline: bool,
+}
+
+impl Normalization {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+        if self.bom {
+            changes.push("removed the byte-order mark".to_string());
+        }
+        if self.line_endings > 0 {
+            changes.push(format!("converted {} CR/CRLF line ending(s)", self.line_endings));
+        }
+        if self.trailing_blank_lines > 0 {
+            changes.push(format!("dropped {} trailing blank line(s)", self.trailing_blank_lines));
+        }
+        if self.final_newline {
+            changes.push("added the final newline".to_string());
+        }
+
+        match changes.is_empty() {
+            true  => write!(f, "no changes"),
+            false => write!(f, "{}", changes.join(", ")),
+        }
+    }
+}
+
+// Brings an input into the form every parser expects: no byte-order mark,
+// `\n` line endings, no blank lines at the end and exactly one final newline.
+// Input that is nothing but whitespace becomes empty.
+pub fn normalize(contents: &str) -> (String, Normalization) {
+    let mut changes = Normalization::default();
+    let mut rest = match contents.strip_prefix('\u{feff}') {
+        Some(rest) => {
+            changes.bom = true;
+            rest
+        },
+        None       => contents,
+    };
+
+    let mut text = String::with_capacity(rest.len() + 1);
+    while let Some(i) = rest.find('\r') {
+        text += &rest[..i];
+        text.push('\n');
+        changes.line_endings += 1;
+        rest = &rest[i + 1..];
+        rest = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+    text += rest;
+    if text.is_empty() {
+        return (text, changes);
+    }
+
+    // Splitting leaves an empty piece after a final newline; it is not a line.
+    let mut lines: Vec<&str> = text.split('\n').collect();
+    let terminated = lines.len() > 1 && lines.last() == Some(&"");
+    if terminated {
+        lines.pop();
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+        changes.trailing_blank_lines += 1;
+    }
+    if lines.is_empty() {
+        return (String::new(), changes);
+    }
+    changes.final_newline = !terminated && changes.trailing_blank_lines == 0;
+
+    (lines.join("\n") + "\n", changes)
+}
+
+// Reads and normalizes a file.
+pub fn read_file(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    std::fs::read_to_string(path)
+        .map(|contents| normalize(&contents).0)
+        .map_err(|source| Error::Io { path: path.display().to_string(), source })
+}
+
+pub fn input_path(dir: impl AsRef<Path>, day: u8) -> PathBuf {
+    dir.as_ref().join(format!("day{:02}.txt", day))
+}
+
+// The input directory: an explicit `dir`, then `$AOC_INPUT_DIR`, then `./inputs`.
+pub fn input_dir(dir: Option<&str>) -> PathBuf {
+    dir.map(PathBuf::from)
+        .or_else(|| env::var_os(INPUT_DIR_VAR).map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR))
+}
+
+// Where to read a day's input from: an explicit path wins, `-` means stdin,
+// otherwise the day's file inside `dir`.
+pub fn resolve(day: u8, explicit: Option<&str>, dir: &Path) -> InputSource {
+    match explicit {
+        Some("-")  => InputSource::Stdin,
+        Some(path) => InputSource::Path(PathBuf::from(path)),
+        None       => InputSource::Path(input_path(dir, day)),
+    }
+}
+
+// Resolves a path to one of a crate's own test fixtures under `src/`,
+// independently of the directory the tests run from.
+#[macro_export]
+macro_rules! fixture {
+    ($name:literal) => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/src/", $name)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_file_reports_missing_path() {
+        let err = read_file("./does-not-exist.dat").unwrap_err();
+        assert!(err.to_string().contains("does-not-exist.dat"));
+    }
+
+    #[test]
+    fn normalizes_line_endings_and_ends() {
+        let (text, changes) = normalize("\u{feff}Time: 7\r\nDistance: 9\r\n\r\n  \n");
+        assert_eq!(text, "Time: 7\nDistance: 9\n");
+        assert_eq!(changes, Normalization { bom: true, line_endings: 3, trailing_blank_lines: 2, final_newline: false });
+
+        let (text, changes) = normalize("a\rb\n\nc");
+        assert_eq!(text, "a\nb\n\nc\n");
+        assert_eq!(changes.to_string(), "converted 1 CR/CRLF line ending(s), added the final newline");
+
+        assert_eq!(normalize("seeds: 1\n"), ("seeds: 1\n".to_string(), Normalization::default()));
+        assert_eq!(normalize("\n\n").0, "");
+        assert_eq!(normalize("").0, "");
+    }
+
+    #[test]
+    fn resolves_in_order_of_precedence() {
+        let dir = Path::new("puzzles");
+
+        assert_eq!(resolve(7, Some("-"), dir), InputSource::Stdin);
+        assert_eq!(resolve(7, Some("my.txt"), dir), InputSource::Path(PathBuf::from("my.txt")));
+        assert_eq!(resolve(7, None, dir), InputSource::Path(PathBuf::from("puzzles/day07.txt")));
+        assert_eq!(input_dir(Some("elsewhere")), PathBuf::from("elsewhere"));
+    }
+}
@@ -0,0 +1,312 @@
+use std::{fmt, ops::Range};
+
+use crate::{checked::overflow, error::Result};
+
+// The half-open range of numbers `start..end`, empty unless `start < end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Interval {
+    pub fn new(start: usize, end: usize) -> Self {
+        Interval { start, end }
+    }
+
+    // The `length` numbers from `start`, an error if they run past the last
+    // `usize`.
+    pub fn from_length(start: usize, length: usize) -> Result<Self> {
+        start.checked_add(length).map(|end| Interval { start, end }).ok_or_else(overflow)
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    // The numbers in both, if there are any.
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let both = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!both.is_empty()).then_some(both)
+    }
+}
+
+impl From<Range<usize>> for Interval {
+    fn from(range: Range<usize>) -> Self {
+        Interval::new(range.start, range.end)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+// A set of numbers kept as the fewest intervals that cover it: in order,
+// none empty and none touching another.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        // The intervals before and after it stay; the ones it meets merge.
+        let first = self.intervals.partition_point(|other| other.end < interval.start);
+        let last = self.intervals.partition_point(|other| other.start <= interval.end);
+        let merged = self.intervals[first..last].iter()
+            .fold(interval, |merged, other| Interval::new(merged.start.min(other.start), merged.end.max(other.end)));
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // How many numbers the set holds.
+    pub fn len(&self) -> usize {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end <= value);
+        self.intervals.get(index).is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals.iter().chain(&other.intervals).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals.iter()
+            .flat_map(|interval| other.intervals.iter().filter_map(|other| interval.intersection(other)))
+            .collect()
+    }
+
+    // The numbers in this set but not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut rest = Vec::new();
+        for &interval in &self.intervals {
+            let mut start = interval.start;
+            for cut in other.intervals.iter().filter_map(|other| interval.intersection(other)) {
+                rest.push(Interval::new(start, cut.start));
+                start = cut.end;
+            }
+            rest.push(Interval::new(start, interval.end));
+        }
+        rest.into_iter().collect()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(intervals: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+// What a `PiecewiseMap` does with numbers none of its pieces cover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unmapped {
+    // They map to themselves.
+    Identity,
+    // They map to nothing.
+    Drop,
+}
+
+// A map that shifts each of a number of source intervals to start at a
+// destination of its own, such as the almanac's maps in day 5.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    // Source intervals in order, disjoint, each with its destination start.
+    pieces: Vec<(Interval, usize)>,
+    unmapped: Unmapped,
+}
+
+impl PiecewiseMap {
+    // The map sending each `(source, destination)` piece's numbers to those
+    // from `destination` on, in order. Where pieces overlap, the first one
+    // listed wins. An error if a destination runs past the last `usize`.
+    pub fn new(pieces: impl IntoIterator<Item = (Interval, usize)>, unmapped: Unmapped) -> Result<Self> {
+        let mut covered = IntervalSet::new();
+        let mut kept = Vec::new();
+        for (source, destination) in pieces {
+            destination.checked_add(source.len()).ok_or_else(overflow)?;
+            let free = IntervalSet::from_iter([source]).difference(&covered);
+            kept.extend(free.intervals().iter().map(|&part| (part, destination + (part.start - source.start))));
+            covered.insert(source);
+        }
+        kept.sort();
+        Ok(PiecewiseMap { pieces: kept, unmapped })
+    }
+
+    // The piece covering `value`, if any.
+    fn piece(&self, value: usize) -> Option<(Interval, usize)> {
+        let index = self.pieces.partition_point(|(source, _)| source.end <= value);
+        self.pieces.get(index).copied().filter(|(source, _)| source.contains(value))
+    }
+
+    // Whether one of the pieces covers `value`.
+    pub fn covers(&self, value: usize) -> bool {
+        self.piece(value).is_some()
+    }
+
+    pub fn map(&self, value: usize) -> Option<usize> {
+        self.map_with_hit(value).0
+    }
+
+    // Where `value` goes, and whether one of the pieces covered it, from a
+    // single lookup.
+    pub fn map_with_hit(&self, value: usize) -> (Option<usize>, bool) {
+        match self.piece(value) {
+            Some((source, destination)) => (Some(destination + (value - source.start)), true),
+            None                        => ((self.unmapped == Unmapped::Identity).then_some(value), false),
+        }
+    }
+
+    // Where the numbers of `interval` go, as intervals: one for each piece it
+    // meets, and for each gap between them unless those are dropped.
+    pub fn map_interval(&self, interval: Interval) -> Vec<Interval> {
+        let mut mapped = Vec::new();
+        let mut start = interval.start;
+        let first = self.pieces.partition_point(|(source, _)| source.end <= interval.start);
+        for &(source, destination) in &self.pieces[first..] {
+            let Some(part) = source.intersection(&interval) else { break };
+            if self.unmapped == Unmapped::Identity && start < part.start {
+                mapped.push(Interval::new(start, part.start));
+            }
+            let offset = part.start - source.start;
+            mapped.push(Interval::new(destination + offset, destination + offset + part.len()));
+            start = part.end;
+        }
+        if self.unmapped == Unmapped::Identity && start < interval.end {
+            mapped.push(Interval::new(start, interval.end));
+        }
+        mapped
+    }
+
+    pub fn map_set(&self, set: &IntervalSet) -> IntervalSet {
+        set.intervals().iter().flat_map(|&interval| self.map_interval(interval)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate::Rng, property::{self, ensure}};
+
+    // Intervals in `0..limit`, some of them empty.
+    fn intervals(rng: &mut Rng, count: usize, limit: usize) -> Vec<Interval> {
+        (0..count).map(|_| {
+            let start = rng.range(0..limit);
+            Interval::new(start, start + rng.range(0..limit / 4 + 1))
+        }).collect()
+    }
+
+    fn members(set: &IntervalSet, limit: usize) -> Vec<usize> {
+        (0..limit).filter(|&value| set.contains(value)).collect()
+    }
+
+    #[test]
+    fn sets_merge_touching_intervals() {
+        let set: IntervalSet = [(5..8).into(), (0..2).into(), (2..3).into(), (7..10).into(), (4..4).into()].into_iter().collect();
+        assert_eq!(set.intervals(), [Interval::new(0, 3), Interval::new(5, 10)]);
+        assert_eq!((set.len(), set.min()), (8, Some(0)));
+        assert!(set.contains(2) && !set.contains(3) && set.contains(9) && !set.contains(10));
+    }
+
+    #[test]
+    fn set_operations_match_membership() {
+        property::check("interval set operations", 20, |rng, size| {
+            let limit = 4 * size + 4;
+            let a: IntervalSet = intervals(rng, size, limit).into_iter().collect();
+            let b: IntervalSet = intervals(rng, size, limit).into_iter().collect();
+            let (in_a, in_b) = (members(&a, 2 * limit), members(&b, 2 * limit));
+
+            let expected = |keep: &dyn Fn(bool, bool) -> bool| -> Vec<usize> {
+                (0..2 * limit).filter(|value| keep(in_a.contains(value), in_b.contains(value))).collect()
+            };
+            ensure(members(&a.union(&b), 2 * limit) == expected(&|a, b| a || b), || format!("{:?} ∪ {:?}", a, b))?;
+            ensure(members(&a.intersection(&b), 2 * limit) == expected(&|a, b| a && b), || format!("{:?} ∩ {:?}", a, b))?;
+            ensure(members(&a.difference(&b), 2 * limit) == expected(&|a, b| a && !b), || format!("{:?} - {:?}", a, b))?;
+
+            // Every result is in its fewest intervals.
+            for set in [a.union(&b), a.intersection(&b), a.difference(&b)] {
+                let tidy = set.intervals().windows(2).all(|pair| pair[0].end < pair[1].start);
+                ensure(tidy && set.intervals().iter().all(|interval| !interval.is_empty()), || format!("untidy {:?}", set))?;
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn pieces_shift_their_numbers() {
+        // Day 5's seed-to-soil map.
+        let map = PiecewiseMap::new([((98..100).into(), 50), ((50..98).into(), 52)], Unmapped::Identity).unwrap();
+        assert_eq!([79, 14, 55, 13, 98, 99].map(|seed| map.map(seed).unwrap()), [81, 14, 57, 13, 50, 51]);
+
+        let mut mapped = map.map_interval((45..100).into());
+        mapped.sort();
+        assert_eq!(mapped, [(45..50).into(), (50..52).into(), (52..100).into()]);
+
+        let dropping = PiecewiseMap::new([((98..100).into(), 50)], Unmapped::Drop).unwrap();
+        assert_eq!((dropping.map(98), dropping.map(3)), (Some(50), None));
+        assert_eq!((map.map_with_hit(98), map.map_with_hit(3)), ((Some(50), true), (Some(3), false)));
+        assert_eq!(dropping.map_interval((90..200).into()), [(50..52).into()]);
+        assert!(PiecewiseMap::new([((0..10).into(), usize::MAX - 5)], Unmapped::Drop).is_err());
+    }
+
+    #[test]
+    fn mapped_intervals_match_mapped_numbers() {
+        property::check("piecewise maps", 20, |rng, size| {
+            let limit = 4 * size + 4;
+            let unmapped = if rng.chance(1, 2) { Unmapped::Identity } else { Unmapped::Drop };
+            let pieces: Vec<(Interval, usize)> = intervals(rng, size, limit).into_iter()
+                .map(|source| (source, rng.range(0..limit)))
+                .collect();
+            let map = PiecewiseMap::new(pieces.clone(), unmapped).unwrap();
+
+            for value in 0..2 * limit {
+                // The first piece listed wins.
+                let expected = pieces.iter().find(|(source, _)| source.contains(value))
+                    .map(|&(source, destination)| destination + value - source.start)
+                    .or((unmapped == Unmapped::Identity).then_some(value));
+                ensure(map.map(value) == expected, || format!("{} maps to {:?}, not {:?}, in {:?}", value, map.map(value), expected, pieces))?;
+            }
+
+            let set: IntervalSet = intervals(rng, size, limit).into_iter().collect();
+            let mut expected: Vec<usize> = members(&set, 2 * limit).into_iter().filter_map(|value| map.map(value)).collect();
+            expected.sort();
+            expected.dedup();
+            let mapped = members(&map.map_set(&set), 4 * limit);
+            ensure(mapped == expected, || format!("{:?} maps to {:?}, not {:?}", set, mapped, expected))
+        });
+    }
+}
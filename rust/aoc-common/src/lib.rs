@@ -0,0 +1,27 @@
+//! Code shared by every day of Advent of Code 2023: input loading, error
+//! types and the `Solution` trait each day implements.
+
+pub mod answers;
+pub mod budget;
+pub mod checked;
+pub mod error;
+pub mod fuzz;
+pub mod generate;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod property;
+pub mod solution;
+pub mod stream;
+pub mod timing;
+pub mod trace;
+
+pub use error::{Error, Result};
+pub use grid::Grid;
+pub use input::{read_file, InputSource};
+pub use parse::{ParseError, Source};
+pub use solution::{Answer, Implementation, Part, Solution, Solver, Streaming};
@@ -0,0 +1,169 @@
+use crate::{checked::overflow, error::Result};
+
+pub fn gcd(a: usize, b: usize) -> usize {
+    gcd_u128(a as u128, b as u128) as usize
+}
+
+pub fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// The least common multiple, 0 if either is; an error if it does not fit.
+pub fn lcm(a: usize, b: usize) -> Result<usize> {
+    lcm_u128(a as u128, b as u128).and_then(|lcm| usize::try_from(lcm).map_err(|_| overflow()))
+}
+
+pub fn lcm_u128(a: u128, b: u128) -> Result<u128> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    (a / gcd_u128(a, b)).checked_mul(b).ok_or_else(overflow)
+}
+
+// `a - q * b`, an error if it overflows.
+fn sub_mul(a: i128, q: i128, b: i128) -> Result<i128> {
+    q.checked_mul(b).and_then(|qb| a.checked_sub(qb)).ok_or_else(overflow)
+}
+
+// Extended Euclid: `(g, x, y)` with `g` the non-negative gcd of `a` and `b`
+// and `a * x + b * y == g`; an error if a number on the way overflows.
+pub fn egcd(a: i128, b: i128) -> Result<(i128, i128, i128)> {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0.checked_div(r1).ok_or_else(overflow)?;
+        (r0, r1) = (r1, sub_mul(r0, q, r1)?);
+        (x0, x1) = (x1, sub_mul(x0, q, x1)?);
+        (y0, y1) = (y1, sub_mul(y0, q, y1)?);
+    }
+    if r0 < 0 {
+        let negate = |n: i128| n.checked_neg().ok_or_else(overflow);
+        Ok((negate(r0)?, negate(x0)?, negate(y0)?))
+    } else {
+        Ok((r0, x0, y0))
+    }
+}
+
+// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn modinv(a: i128, m: i128) -> Result<Option<i128>> {
+    let (g, x, _) = egcd(a.rem_euclid(m), m)?;
+    Ok((g == 1).then(|| x.rem_euclid(m)))
+}
+
+// Chinese remainder theorem for any moduli, coprime or not: the `(x, m)`
+// such that the numbers meeting every `(residue, modulus)` congruence are
+// exactly those `≡ x (mod m)`, with `x` in `0..m`. `None` if no number meets
+// them all, an error if `m` or the numbers on the way to it overflow.
+// Panics on a modulus below 1.
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Result<Option<(i128, i128)>> {
+    let (mut x, mut m) = (0_i128, 1_i128);
+    for (residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus {} is not positive", modulus);
+        let residue = residue.rem_euclid(modulus);
+
+        // x + m * k ≡ residue (mod modulus), solved for k.
+        let (g, inverse, _) = egcd(m, modulus)?;
+        let difference = residue.checked_sub(x).ok_or_else(overflow)?;
+        if difference % g != 0 {
+            return Ok(None);
+        }
+        let step = modulus / g;
+        let k = (difference / g).rem_euclid(step)
+            .checked_mul(inverse.rem_euclid(step))
+            .ok_or_else(overflow)?
+            % step;
+
+        let lcm = m.checked_mul(step).ok_or_else(overflow)?;
+        x = m.checked_mul(k).and_then(|mk| mk.checked_add(x)).ok_or_else(overflow)? % lcm;
+        m = lcm;
+    }
+    Ok(Some((x, m)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn gcd_and_lcm_match_brute_force() {
+        for a in 0..60 {
+            for b in 0..60 {
+                let divisor = (1..=a.max(b)).rev().find(|d| a % d == 0 && b % d == 0).unwrap_or(0);
+                assert_eq!(gcd(a, b), divisor, "gcd({}, {})", a, b);
+
+                let multiple = if a == 0 || b == 0 { 0 } else { (1..=a * b).find(|m| m % a == 0 && m % b == 0).unwrap() };
+                assert_eq!(lcm(a, b).unwrap(), multiple, "lcm({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn lcm_overflow_is_an_error() {
+        let prime = 4_294_967_311_usize;
+        assert_eq!(lcm(prime, prime).unwrap(), prime);
+        assert!(matches!(lcm(prime, prime + 2), Err(Error::Overflow)));
+        assert_eq!(lcm_u128(prime as u128, prime as u128 + 2).unwrap(), prime as u128 * (prime as u128 + 2));
+        assert!(lcm_u128(u128::MAX, u128::MAX - 1).is_err());
+        assert_eq!(gcd_u128(u128::MAX, u128::MAX - 2), 1);
+    }
+
+    #[test]
+    fn egcd_finds_bezout_coefficients() {
+        for a in -40..40_i128 {
+            for b in -40..40 {
+                let (g, x, y) = egcd(a, b).unwrap();
+                assert_eq!(g, gcd(a.unsigned_abs() as usize, b.unsigned_abs() as usize) as i128, "egcd({}, {})", a, b);
+                assert_eq!(a * x + b * y, g, "egcd({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn modinv_matches_brute_force() {
+        for m in 1..40_i128 {
+            for a in -40..40_i128 {
+                let inverse = (0..m).find(|x| (a * x).rem_euclid(m) == 1 % m);
+                assert_eq!(modinv(a, m).unwrap(), inverse, "modinv({}, {})", a, m);
+            }
+        }
+    }
+
+    #[test]
+    fn crt_matches_brute_force() {
+        for m1 in 1..13_i128 {
+            for m2 in 1..13_i128 {
+                for a1 in 0..m1 {
+                    for a2 in -1..m2 {
+                        let lcm = lcm(m1 as usize, m2 as usize).unwrap() as i128;
+                        let x = (0..lcm).find(|x| (x - a1).rem_euclid(m1) == 0 && (x - a2).rem_euclid(m2) == 0);
+                        assert_eq!(crt([(a1, m1), (a2, m2)]).unwrap(), x.map(|x| (x, lcm)), "x ≡ {} mod {}, {} mod {}", a1, m1, a2, m2);
+                    }
+                }
+            }
+        }
+        assert_eq!(crt([]).unwrap(), Some((0, 1)));
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]).unwrap(), Some((23, 105)));
+    }
+
+    #[test]
+    fn crt_overflow_is_an_error() {
+        let big = (1_i128 << 61) - 1;
+        assert_eq!(crt([(1, big), (2, big - 2)]).unwrap().map(|(_, m)| m), Some(big * (big - 2)));
+        assert!(matches!(crt([(1, big), (2, big - 2), (3, big - 4)]), Err(Error::Overflow)));
+
+        // At the ends of i128 Euclid's own steps overflow: the gcd of
+        // i128::MIN and 0 does not fit, nor does i128::MIN / -1.
+        assert_eq!(egcd(i128::MAX, i128::MAX - 1).unwrap(), (1, 1, -1));
+        assert!(matches!(egcd(i128::MIN, 0), Err(Error::Overflow)));
+        assert!(matches!(egcd(i128::MIN, -1), Err(Error::Overflow)));
+        assert_eq!(modinv(i128::MAX - 1, i128::MAX).unwrap(), Some(i128::MAX - 1));
+        assert_eq!(crt([(5, i128::MAX)]).unwrap(), Some((5, i128::MAX)));
+        assert!(matches!(crt([(1, i128::MAX), (2, i128::MAX - 1)]), Err(Error::Overflow)));
+        assert!(matches!(crt([(1, i128::MAX), (2, i128::MAX)]), Ok(None)));
+    }
+}
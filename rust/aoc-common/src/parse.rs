@@ -0,0 +1,169 @@
+use std::{fmt, str::FromStr};
+
+use crate::error::{Error, Result};
+
+// Where a parser gave up: 1-based line and column (in characters), the text
+// it found there and a description of what it wanted instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub found: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, found: impl Into<String>, expected: impl Into<String>) -> Self {
+        Self { line, column, found: found.into(), expected: expected.into() }
+    }
+
+    // The offending line of `source` with a marker under the text that failed
+    // to parse, or nothing if the line is not part of `source`.
+    pub fn snippet(&self, source: &str) -> Option<String> {
+        let text = source.lines().nth(self.line.checked_sub(1)?)?;
+
+        let gutter = self.line.to_string().len();
+        let width = self.found.chars().count().max(1);
+        Some(format!("{:>gutter$} |\n{} | {}\n{:>gutter$} | {}{}",
+            "", self.line, text, "", " ".repeat(self.column.saturating_sub(1)), "^".repeat(width)))
+    }
+
+    pub fn render(&self, source: &str) -> String {
+        match self.snippet(source) {
+            Some(snippet) => format!("{}\n{}", self, snippet),
+            None          => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: expected {}, found ", self.line, self.column, self.expected)?;
+        if self.found.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "{:?}", self.found)
+        }
+    }
+}
+
+// Wraps the raw puzzle input so that parsers can report errors against any
+// slice they borrowed from it, without tracking positions by hand.
+#[derive(Debug, Clone, Copy)]
+pub struct Source<'a> {
+    contents: &'a str,
+    first_line: usize,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(contents: &'a str) -> Self {
+        Self::at_line(contents, 1)
+    }
+
+    // A piece of a larger input that starts on line `first_line` of it, so
+    // that errors are still reported against the whole input.
+    pub fn at_line(contents: &'a str, first_line: usize) -> Self {
+        Self { contents, first_line }
+    }
+
+    pub fn contents(&self) -> &'a str {
+        self.contents
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'a> {
+        self.contents.lines()
+    }
+
+    // An empty slice at the very end of the input, for reporting missing lines.
+    pub fn end(&self) -> &'a str {
+        &self.contents[self.contents.len()..]
+    }
+
+    // An empty slice just past `fragment`, for reporting what is missing after it.
+    pub fn after(&self, fragment: &'a str) -> &'a str {
+        let offset = self.offset(fragment) + fragment.len();
+        &self.contents[offset..offset]
+    }
+
+    fn offset(&self, fragment: &str) -> usize {
+        let start = self.contents.as_ptr() as usize;
+        let at = fragment.as_ptr() as usize;
+        if (start..=start + self.contents.len()).contains(&at) {
+            at - start
+        } else {
+            self.contents.find(fragment).unwrap_or(self.contents.len())
+        }
+    }
+
+    pub fn locate(&self, fragment: &str) -> (usize, usize) {
+        let offset = self.offset(fragment);
+        let before = &self.contents[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + self.first_line;
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+
+    pub fn error(&self, fragment: &str, expected: impl Into<String>) -> Error {
+        let (line, column) = self.locate(fragment);
+        Error::Parse(ParseError::new(line, column, fragment, expected))
+    }
+
+    pub fn number<T: FromStr>(&self, fragment: &'a str) -> Result<T> {
+        fragment.parse().map_err(|_| self.error(fragment, "a number"))
+    }
+
+    pub fn numbers<T: FromStr>(&self, fragment: &'a str) -> Result<Vec<T>> {
+        fragment.split_whitespace().map(|word| self.number(word)).collect()
+    }
+
+    pub fn split_once(&self, fragment: &'a str, delimiter: &str) -> Result<(&'a str, &'a str)> {
+        fragment.split_once(delimiter)
+            .ok_or_else(|| self.error(fragment, format!("{:?}", delimiter)))
+    }
+
+    pub fn strip_prefix(&self, fragment: &'a str, prefix: &str) -> Result<&'a str> {
+        fragment.strip_prefix(prefix)
+            .ok_or_else(|| self.error(fragment, format!("{:?}", prefix)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_fragments() {
+        let source = Source::new("first line\nsecond 42x\n");
+        let line = source.lines().nth(1).unwrap();
+        let word = line.split_whitespace().nth(1).unwrap();
+
+        assert_eq!(source.locate(word), (2, 8));
+        match source.number::<usize>(word) {
+            Err(Error::Parse(err)) => assert_eq!(err, ParseError::new(2, 8, "42x", "a number")),
+            other                  => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_missing_delimiter_at_line_end() {
+        let source = Source::new("Game 1");
+        let line = source.lines().next().unwrap();
+        let err = source.split_once(line, ": ").unwrap_err();
+        assert_eq!(err.to_string(), "parse error: line 1, column 1: expected \": \", found \"Game 1\"");
+
+        let end = source.after(line);
+        assert_eq!(source.locate(end), (1, 7));
+    }
+
+    #[test]
+    fn renders_snippet() {
+        let source = "Game 1: 3 blue\nGame 2: 4 purple\n";
+        let err = ParseError::new(2, 11, "purple", "a cube colour");
+        assert_eq!(err.render(source), "\
+line 2, column 11: expected a cube colour, found \"purple\"
+  |
+2 | Game 2: 4 purple
+  |           ^^^^^^");
+    }
+}
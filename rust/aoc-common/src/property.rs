@@ -0,0 +1,94 @@
+use std::{
+    env,
+    panic::{self, AssertUnwindSafe},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{fuzz::panic_message, generate::Rng};
+
+// Reruns a single failing case: the seed and size a failure reports.
+pub const SEED_VAR: &str = "AOC_PROPERTY_SEED";
+pub const SIZE_VAR: &str = "AOC_PROPERTY_SIZE";
+// How many cases each property tries, 100 unless set.
+pub const CASES_VAR: &str = "AOC_PROPERTY_CASES";
+
+// What a property returns: nothing, or why the case broke it.
+pub type Outcome = std::result::Result<(), String>;
+
+// Fails a case with `message` unless `condition` holds.
+pub fn ensure(condition: bool, message: impl FnOnce() -> String) -> Outcome {
+    if condition { Ok(()) } else { Err(message()) }
+}
+
+fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().map(|value| value.parse().unwrap_or_else(|_| panic!("{} is not a number: '{}'", name, value)))
+}
+
+// One case: the property on a fresh generator, a panic counting as a failure.
+fn attempt(property: &impl Fn(&mut Rng, usize) -> Outcome, seed: u64, size: usize) -> Outcome {
+    panic::catch_unwind(AssertUnwindSafe(|| property(&mut Rng::new(seed), size)))
+        .unwrap_or_else(|payload| Err(format!("panicked: {}", panic_message(payload.as_ref()))))
+}
+
+// Checks `property` on random cases of growing size, up to `max_size`, each
+// drawing from a generator seeded afresh. The seeds differ on every run; a
+// failing case is shrunk to the smallest size at which its seed still fails,
+// and the panic names the seed and size that reproduce it.
+pub fn check(name: &str, max_size: usize, property: impl Fn(&mut Rng, usize) -> Outcome) {
+    if let Some(seed) = var(SEED_VAR) {
+        let size = var(SIZE_VAR).unwrap_or(max_size);
+        if let Err(message) = attempt(&property, seed, size) {
+            panic!("property {} failed for seed {} at size {}: {}", name, seed, size, message);
+        }
+        return;
+    }
+
+    let cases: u64 = var(CASES_VAR).unwrap_or(100).max(1);
+    let base = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_nanos() as u64) ^ std::process::id() as u64;
+    for case in 0..cases {
+        let seed = Rng::new(base.wrapping_add(case)).next_u64();
+        let size = 1 + (max_size.saturating_sub(1) as u64 * case / cases.max(2).saturating_sub(1)) as usize;
+        let Err(message) = attempt(&property, seed, size) else { continue };
+
+        // Halve the size while the seed keeps failing.
+        let (mut size, mut message) = (size, message);
+        while size > 1 {
+            match attempt(&property, seed, size / 2) {
+                Err(smaller) => (size, message) = (size / 2, smaller),
+                Ok(())       => break,
+            }
+        }
+        panic!("property {} failed for seed {} at size {}: {}\nrerun it with {}={} {}={}",
+            name, seed, size, message, SEED_VAR, seed, SIZE_VAR, size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passing_properties_pass() {
+        check("sizes stay in bounds", 50, |rng, size| {
+            let value = rng.range(0..size + 1);
+            ensure((1..=50).contains(&size) && value <= size, || format!("{} of {}", value, size))
+        });
+    }
+
+    #[test]
+    fn failures_shrink_and_name_their_seed() {
+        let failure = panic::catch_unwind(|| check("small", 1000, |_, size| ensure(size < 10, || format!("size {}", size))))
+            .unwrap_err();
+        let message = failure.downcast_ref::<String>().unwrap();
+
+        assert!(message.starts_with("property small failed for seed "), "{}", message);
+        assert!(message.contains(" at size ") && message.contains(&format!("{}=", SEED_VAR)), "{}", message);
+        let size: usize = message.split(" at size ").nth(1).unwrap().split(':').next().unwrap().parse().unwrap();
+        assert!((10..20).contains(&size), "shrunk to {}", size);
+    }
+
+    #[test]
+    fn panics_fail_the_case() {
+        assert_eq!(attempt(&|_: &mut Rng, _| panic!("boom"), 1, 1), Err("panicked: boom".to_string()));
+    }
+}
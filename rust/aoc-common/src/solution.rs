@@ -0,0 +1,229 @@
+use std::{any::Any, fmt, io::BufRead};
+
+use crate::error::{Error, Result};
+
+// Every part answers with either a number or, for the odd puzzle, some text.
+// Numbers are widened to i128 so that `usize` and `isize` answers compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s)   => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Number(value as i128)
+            }
+        })*
+    };
+}
+
+answer_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+// A named way of solving one part from the parsed input, for days that keep
+// more than one to compare.
+pub struct Implementation<I> {
+    pub name: &'static str,
+    pub part: Part,
+    pub solve: fn(&I) -> Result<Answer>,
+}
+
+// A day parses its input once and then answers both parts from the parsed form.
+// Parts left unsolved fall back to `Error::NotImplemented`.
+pub trait Solution {
+    type Input;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input>;
+
+    fn part_1(&self, _input: &Self::Input) -> Result<Answer> {
+        Err(Error::NotImplemented)
+    }
+
+    fn part_2(&self, _input: &Self::Input) -> Result<Answer> {
+        Err(Error::NotImplemented)
+    }
+
+    // Every way of solving a part when there is more than one: the one that
+    // `part_1` or `part_2` uses first, then the alternatives.
+    fn implementations(&self) -> Vec<Implementation<Self::Input>> {
+        Vec::new()
+    }
+}
+
+// A day that can also solve straight from a reader, one line at a time,
+// for inputs too large to hold in memory. `path` is where the reader reads
+// from, for its errors.
+pub trait Streaming: Sync {
+    fn stream_part_1(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer>;
+    fn stream_part_2(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer>;
+
+    fn stream(&self, part: Part, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        match part {
+            Part::One => self.stream_part_1(reader, path),
+            Part::Two => self.stream_part_2(reader, path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+// Object-safe view of a `Solution`, so that the runner can keep every day in
+// one table. The parsed input travels as `dyn Any` between `parse` and `solve`.
+pub trait Solver: Sync {
+    fn parse(&self, contents: &str) -> Result<Box<dyn Any>>;
+    fn solve(&self, part: Part, input: &dyn Any) -> Result<Answer>;
+
+    // The names of a part's implementations, empty unless it has several.
+    fn implementations(&self, part: Part) -> Vec<&'static str>;
+    // Solves with the implementation called `name`, if the part has one.
+    fn solve_with(&self, part: Part, name: &str, input: &dyn Any) -> Result<Answer>;
+}
+
+impl<S> Solver for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn parse(&self, contents: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(Solution::parse(self, contents)?))
+    }
+
+    fn solve(&self, part: Part, input: &dyn Any) -> Result<Answer> {
+        let input = input.downcast_ref::<S::Input>()
+            .expect("input was parsed by a different solver");
+        match part {
+            Part::One => self.part_1(input),
+            Part::Two => self.part_2(input),
+        }
+    }
+
+    fn implementations(&self, part: Part) -> Vec<&'static str> {
+        Solution::implementations(self).into_iter()
+            .filter(|implementation| implementation.part == part)
+            .map(|implementation| implementation.name)
+            .collect()
+    }
+
+    fn solve_with(&self, part: Part, name: &str, input: &dyn Any) -> Result<Answer> {
+        let input = input.downcast_ref::<S::Input>()
+            .expect("input was parsed by a different solver");
+        match Solution::implementations(self).into_iter().find(|implementation| implementation.part == part && implementation.name == name) {
+            Some(implementation) => (implementation.solve)(input),
+            None                 => Err(Error::NotImplemented),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Unsolved;
+
+    impl Solution for Unsolved {
+        type Input = ();
+
+        fn parse(&self, _contents: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Doubler;
+
+    impl Solution for Doubler {
+        type Input = Vec<usize>;
+
+        fn parse(&self, contents: &str) -> Result<Self::Input> {
+            Ok(contents.split_whitespace().map(|s| s.parse().unwrap()).collect())
+        }
+
+        fn part_1(&self, input: &Self::Input) -> Result<Answer> {
+            Ok(input.iter().map(|v| v * 2).sum::<usize>().into())
+        }
+
+        fn implementations(&self) -> Vec<Implementation<Self::Input>> {
+            vec![
+                Implementation { name: "sum", part: Part::One, solve: |input| Doubler.part_1(input) },
+                Implementation { name: "shift", part: Part::One, solve: |input| Ok(input.iter().map(|v| v << 1).sum::<usize>().into()) },
+            ]
+        }
+    }
+
+    #[test]
+    fn solver_parses_once_for_both_parts() {
+        let solver: &dyn Solver = &Doubler;
+        let input = solver.parse("1 2 3").unwrap();
+
+        assert_eq!(solver.solve(Part::One, input.as_ref()).unwrap(), 12.into());
+        assert!(matches!(solver.solve(Part::Two, input.as_ref()), Err(Error::NotImplemented)));
+    }
+
+    #[test]
+    fn implementations_are_found_by_name() {
+        let solver: &dyn Solver = &Doubler;
+        let input = solver.parse("1 2 3").unwrap();
+
+        assert_eq!(solver.implementations(Part::One), ["sum", "shift"]);
+        assert!(solver.implementations(Part::Two).is_empty());
+        assert_eq!(solver.solve_with(Part::One, "shift", input.as_ref()).unwrap(), 12.into());
+        assert!(matches!(solver.solve_with(Part::Two, "shift", input.as_ref()), Err(Error::NotImplemented)));
+        assert!(Solution::implementations(&Unsolved).is_empty());
+    }
+
+    #[test]
+    fn numbers_compare_across_types() {
+        assert_eq!(Answer::from(42usize), Answer::from(42isize));
+        assert_eq!(Answer::from(-2isize).to_string(), "-2");
+    }
+
+    #[test]
+    fn unsolved_parts_are_not_implemented() {
+        assert!(Solution::parse(&Unsolved, "").is_ok());
+        assert!(matches!(Unsolved.part_1(&()), Err(Error::NotImplemented)));
+        assert!(matches!(Unsolved.part_2(&()), Err(Error::NotImplemented)));
+    }
+}
@@ -0,0 +1,147 @@
+use std::{collections::VecDeque, io::BufRead};
+
+use crate::{
+    error::{Error, Result},
+    parse::Source,
+};
+
+// The lines of a reader with their 1-based numbers and without line endings,
+// read one at a time so that memory use is bounded by the longest run of
+// lines. They are exactly the lines of `input::normalize`: a leading
+// byte-order mark is dropped, `\r\n` and a lone `\r` end a line like `\n`,
+// and whitespace-only lines are held back until a later line shows they are
+// not trailing, then returned as they are.
+pub struct Lines<R> {
+    reader: R,
+    // Where the reader reads from, for its errors.
+    path: String,
+    number: usize,
+    // Lines split off the last read at a lone `\r`, not looked at yet.
+    unread: VecDeque<String>,
+    // Whitespace-only lines that may still turn out to be trailing.
+    blank: Vec<String>,
+    // Lines known not to be trailing, to return in order.
+    ready: VecDeque<String>,
+}
+
+impl<R: BufRead> Lines<R> {
+    fn read(&mut self) -> Result<Option<String>> {
+        if let Some(line) = self.unread.pop_front() {
+            return Ok(Some(line));
+        }
+        let first = self.number == 0 && self.blank.is_empty() && self.ready.is_empty();
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0)       => Ok(None),
+            Ok(_)       => {
+                if line.ends_with('\n') {
+                    line.pop();
+                }
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+                if first && line.starts_with('\u{feff}') {
+                    line.remove(0);
+                }
+                let mut pieces = line.split('\r').map(str::to_string);
+                let line = pieces.next().unwrap_or_default();
+                self.unread.extend(pieces);
+                Ok(Some(line))
+            },
+            Err(source) => Err(Error::Io { path: self.path.clone(), source }),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(line) = self.ready.pop_front() {
+                self.number += 1;
+                return Some(Ok((self.number, line)));
+            }
+
+            match self.read() {
+                Ok(Some(line)) if line.trim().is_empty() => self.blank.push(line),
+                Ok(Some(line))                           => {
+                    self.ready.extend(self.blank.drain(..));
+                    self.ready.push_back(line);
+                },
+                Ok(None)                                 => return None,
+                Err(err)                                 => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+// The path errors reading text already in memory would be reported under,
+// though reading it cannot fail.
+pub const IN_MEMORY: &str = "<memory>";
+
+// The lines of `reader`, which reads from `path`.
+pub fn lines<R: BufRead>(reader: R, path: &str) -> Lines<R> {
+    Lines { reader, path: path.to_string(), number: 0, unread: VecDeque::new(), blank: Vec::new(), ready: VecDeque::new() }
+}
+
+// Parses each line of `reader` with `f` as it is read. `f` gets the line as a
+// `Source` that reports errors at the line's position in the whole input.
+pub fn parse_lines<R: BufRead, T>(reader: R, path: &str, mut f: impl FnMut(Source) -> Result<T>) -> impl Iterator<Item = Result<T>> {
+    lines(reader, path).map(move |line| {
+        let (number, text) = line?;
+        f(Source::at_line(&text, number))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{self, ensure};
+
+    fn streamed(contents: &str) -> Vec<(usize, String)> {
+        lines(contents.as_bytes(), IN_MEMORY).map(|line| line.unwrap()).collect()
+    }
+
+    fn normalized(contents: &str) -> Vec<(usize, String)> {
+        crate::input::normalize(contents).0.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn lines_match_normalized_input() {
+        for contents in ["first\r\nsecond\n\nlast", "\u{feff}a\n\n\nb\n\n \n", "", "\n\n", "a\n  \n\t\nb\n", "a\rb\r\r\n c\r"] {
+            assert_eq!(streamed(contents), normalized(contents), "{:?}", contents);
+        }
+        assert_eq!(streamed("a\n  \nb")[1], (2, "  ".to_string()));
+    }
+
+    #[test]
+    fn any_text_streams_like_it_normalizes() {
+        property::check("streamed lines", 50, |rng, size| {
+            let contents: String = (0..size).map(|_| *rng.pick(&['a', ' ', '\t', '\r', '\n', '\u{feff}'])).collect();
+            ensure(streamed(&contents) == normalized(&contents), || format!("{:?}", contents))
+        });
+    }
+
+    #[test]
+    fn read_errors_name_the_path() {
+        let mut streamed = lines(&b"ok\n\xff\n"[..], "inputs/big.txt");
+        assert_eq!(streamed.next().unwrap().unwrap(), (1, "ok".to_string()));
+        match streamed.next() {
+            Some(Err(Error::Io { path, .. })) => assert_eq!(path, "inputs/big.txt"),
+            other                             => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn errors_keep_their_line() {
+        let parsed: Result<Vec<u32>> = parse_lines("1\n2\nx3\n".as_bytes(), IN_MEMORY, |source| source.number(source.contents())).collect();
+        match parsed {
+            Err(Error::Parse(err)) => assert_eq!((err.line, err.column, err.found.as_str()), (3, 1, "x3")),
+            other                  => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::time::{Duration, Instant};
+
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+// Human-friendly duration with three significant digits, e.g. `41.2µs`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    let (value, unit) = match nanos {
+        n if n < 1e3 => (n, "ns"),
+        n if n < 1e6 => (n / 1e3, "µs"),
+        n if n < 1e9 => (n / 1e6, "ms"),
+        n            => (n / 1e9, "s"),
+    };
+    match value {
+        v if v < 10.0  => format!("{:.2}{}", v, unit),
+        v if v < 100.0 => format!("{:.1}{}", v, unit),
+        v              => format!("{:.0}{}", v, unit),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    // Nearest-rank statistics over the samples; `None` when there are none.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        let rank = |p: usize| samples[((samples.len() * p).div_ceil(100)).max(1) - 1];
+        Some(Self {
+            runs: samples.len(),
+            min: samples[0],
+            median: rank(50),
+            p95: rank(95),
+        })
+    }
+}
+
+// Runs `f` `warmup` times untimed, then `iterations` timed runs. Stops at the
+// first error so a failing part is not benchmarked.
+pub fn bench<T, E>(warmup: usize, iterations: usize, mut f: impl FnMut() -> Result<T, E>) -> Result<Option<Stats>, E> {
+    for _ in 0..warmup {
+        f()?;
+    }
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let (result, elapsed) = time(&mut f);
+        result?;
+        samples.push(elapsed);
+    }
+    Ok(Stats::from_samples(samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats_use_nearest_rank() {
+        let stats = Stats::from_samples((1..=20).rev().map(ms).collect()).unwrap();
+        assert_eq!(stats, Stats { runs: 20, min: ms(1), median: ms(10), p95: ms(19) });
+
+        let single = Stats::from_samples(vec![ms(3)]).unwrap();
+        assert_eq!((single.min, single.median, single.p95), (ms(3), ms(3), ms(3)));
+        assert!(Stats::from_samples(vec![]).is_none());
+    }
+
+    #[test]
+    fn bench_stops_at_first_error() {
+        let mut calls = 0;
+        let result: Result<Option<Stats>, &str> = bench(2, 5, || {
+            calls += 1;
+            if calls == 4 { Err("boom") } else { Ok(()) }
+        });
+        assert_eq!(result, Err("boom"));
+        assert_eq!(calls, 4);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(Duration::from_nanos(512)), "512ns");
+        assert_eq!(format_duration(Duration::from_nanos(41_234)), "41.2µs");
+        assert_eq!(format_duration(ms(1500)), "1.50s");
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    fmt,
+};
+
+// How much a solver reports: `Info` for a handful of events per part (`-v`),
+// `Debug` for one per step of the interesting loops (`-vv`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info = 1,
+    Debug = 2,
+}
+
+impl Level {
+    // The level `-v` repeated `verbosity` times asks for, if any.
+    pub fn from_verbosity(verbosity: u8) -> Option<Self> {
+        match verbosity {
+            0 => None,
+            1 => Some(Level::Info),
+            _ => Some(Level::Debug),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Info  => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+// A named event with `key=value` fields, e.g. `day10.loop length=140`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub level: Level,
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.level, self.name)?;
+        for (key, value) in &self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+// Everything a traced run emitted: its events in order and the final value
+// of every counter it touched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub events: Vec<Event>,
+    pub counters: BTreeMap<&'static str, u64>,
+}
+
+impl Trace {
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.counters.is_empty()
+    }
+
+    // Adds `other`'s events after this trace's own and sums the counters.
+    pub fn append(&mut self, other: Trace) {
+        self.events.extend(other.events);
+        for (name, value) in other.counters {
+            *self.counters.entry(name).or_default() += value;
+        }
+    }
+}
+
+// One indented line per event, then one per counter.
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            writeln!(f, "  {}", event)?;
+        }
+        for (name, value) in &self.counters {
+            writeln!(f, "  {} = {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    // The level being collected on this thread, 0 when nothing is. Kept apart
+    // from the trace so that `enabled` is a single load.
+    static LEVEL: Cell<u8> = const { Cell::new(0) };
+    static TRACE: RefCell<Trace> = RefCell::new(Trace::default());
+}
+
+// Runs `f`, collecting whatever it emits on this thread at `level` or below.
+pub fn collect<T>(level: Level, f: impl FnOnce() -> T) -> (T, Trace) {
+    let restore = Restore {
+        level: LEVEL.with(|current| current.replace(level as u8)),
+        trace: Some(TRACE.with(|current| current.take())),
+    };
+    let value = f();
+    let trace = TRACE.with(|current| current.take());
+    drop(restore);
+    (value, trace)
+}
+
+// Puts back the enclosing collection, if any, even if `f` panics.
+struct Restore {
+    level: u8,
+    trace: Option<Trace>,
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        LEVEL.with(|current| current.set(self.level));
+        let previous = self.trace.take().unwrap_or_default();
+        TRACE.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+// Whether events at `level` are being collected. Solvers rarely need this
+// directly: `event!` checks it before formatting any field.
+#[inline]
+pub fn enabled(level: Level) -> bool {
+    LEVEL.with(Cell::get) >= level as u8
+}
+
+// Records an event; use `event!`, which skips the formatting when disabled.
+pub fn record(level: Level, name: &'static str, fields: Vec<(&'static str, String)>) {
+    if enabled(level) {
+        TRACE.with(|current| current.borrow_mut().events.push(Event { level, name, fields }));
+    }
+}
+
+// Adds `n` to the counter `name` while anything is being collected.
+#[inline]
+pub fn count(name: &'static str, n: u64) {
+    if enabled(Level::Info) {
+        TRACE.with(|current| *current.borrow_mut().counters.entry(name).or_default() += n);
+    }
+}
+
+// Emits an event with `key = value` fields, formatting the values only when
+// the level is enabled:
+//
+//     event!(Level::Debug, "day4.copies", card = number, copies = copies);
+#[macro_export]
+macro_rules! event {
+    ($level:expr, $name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled($level) {
+            $crate::trace::record($level, $name, vec![$((stringify!($key), ::std::string::ToString::to_string(&$value))),*]);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work() -> u64 {
+        event!(Level::Info, "work.start", items = 3);
+        for item in 0..3 {
+            count("work.items", 1);
+            event!(Level::Debug, "work.item", item = item, square = item * item);
+        }
+        42
+    }
+
+    #[test]
+    fn nothing_is_kept_when_disabled() {
+        assert!(!enabled(Level::Info));
+        assert_eq!(work(), 42);
+        assert_eq!(collect(Level::Info, || ()).1, Trace::default());
+    }
+
+    #[test]
+    fn levels_filter_events() {
+        let (value, info) = collect(Level::Info, work);
+        assert_eq!(value, 42);
+        assert_eq!(info.to_string(), "  [info] work.start items=3\n  work.items = 3\n");
+
+        let (_, debug) = collect(Level::Debug, work);
+        assert_eq!(debug.events.len(), 4);
+        assert_eq!(debug.events[3].to_string(), "[debug] work.item item=2 square=4");
+    }
+
+    #[test]
+    fn collections_nest() {
+        let (inner, outer) = collect(Level::Info, || {
+            count("outer", 1);
+            let (_, inner) = collect(Level::Debug, work);
+            count("outer", 1);
+            inner
+        });
+        assert_eq!(inner.counters["work.items"], 3);
+        assert_eq!(outer.counters.get("work.items"), None);
+        assert_eq!(outer.counters["outer"], 2);
+        assert!(!enabled(Level::Info));
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+use aoc_common::{
+    answers::{fingerprint, AnswerBook, Verdict, ANSWERS_FILE},
+    timing, Part,
+};
+
+use crate::{
+    registry::Entry,
+    run::{self, Harness, Outcome, Report},
+};
+
+// The inputs to run in `dir`: every regular file except hidden ones and the
+// answer book, sorted by name.
+pub fn input_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry.file_type()?.is_file() && !name.starts_with('.') && name != ANSWERS_FILE {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// One part solved for one input, with what the answer book made of it when
+// checking.
+#[derive(Debug)]
+pub struct Row {
+    pub input: String,
+    pub report: Report,
+    pub verdict: Option<Verdict>,
+}
+
+impl Row {
+    pub fn failed(&self) -> bool {
+        self.report.failed() || matches!(self.verdict, Some(Verdict::Mismatch { .. }))
+    }
+}
+
+// Solves every part of the day for each named input, checking the answers
+// against `book` if there is one.
+pub fn batch_day(entry: &Entry, inputs: &[(String, Result<String, String>)], parts: &[Part], harness: &Harness, book: Option<&AnswerBook>) -> Vec<Row> {
+    let mut rows = Vec::new();
+
+    for (input, contents) in inputs {
+        let (reports, fingerprint) = match contents {
+            Ok(contents) => (run::run_day(entry, contents, parts, harness), fingerprint(contents)),
+            Err(message) => {
+                let failed = parts.iter().map(|&part| Report::new(entry.day, part, Outcome::Failed(message.clone())));
+                (failed.collect(), String::new())
+            },
+        };
+
+        for report in reports {
+            let verdict = match (&report.outcome, book) {
+                (Outcome::Solved(answer), Some(book)) => Some(book.check(report.day, report.part, &fingerprint, answer)),
+                _                                     => None,
+            };
+            rows.push(Row { input: input.clone(), report, verdict });
+        }
+    }
+
+    rows
+}
+
+// A table with one line per input and part. The parse time is shared by the
+// parts of an input, so it is shown on the first of them only; the check
+// column appears when the answers were checked.
+pub fn render(rows: &[Row], checked: bool) -> String {
+    let mut header = vec!["input", "part", "answer", "parse", "solve"];
+    if checked {
+        header.push("check");
+    }
+    let mut table: Vec<Vec<String>> = vec![header.into_iter().map(String::from).collect()];
+
+    for (i, row) in rows.iter().enumerate() {
+        let first = i == 0 || rows[i - 1].input != row.input;
+        let duration = |duration: Option<_>| duration.map(timing::format_duration).unwrap_or_default();
+        let mut cells = vec![
+            row.input.clone(),
+            row.report.part.to_string(),
+            match &row.report.outcome {
+                Outcome::Solved(answer)  => answer.to_string(),
+                Outcome::NotImplemented  => "not done yet".to_string(),
+                Outcome::Failed(message) => format!("error: {}", message),
+            },
+            if first { duration(row.report.parse) } else { String::new() },
+            duration(row.report.solve),
+        ];
+        if checked {
+            cells.push(match &row.verdict {
+                Some(Verdict::Match)                 => "ok".to_string(),
+                Some(Verdict::Mismatch { expected }) => format!("MISMATCH (expected {})", expected),
+                Some(Verdict::Unknown)               => "unknown".to_string(),
+                None                                 => String::new(),
+            });
+        }
+        table.push(cells);
+    }
+
+    layout(&table)
+}
+
+// Lines up the cells of `table` in columns two spaces apart.
+pub fn layout(table: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = (0..table.first().map_or(0, Vec::len))
+        .map(|column| table.iter().map(|cells| cells[column].chars().count()).max().unwrap_or(0))
+        .collect();
+    table.iter()
+        .map(|cells| {
+            let line: Vec<String> = cells.iter().zip(&widths).map(|(cell, &width)| format!("{:width$}", cell)).collect();
+            format!("{}\n", line.join("  ").trim_end())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn renders_a_table() {
+        let report = |part, outcome, parse: Option<u64>| Report {
+            parse: parse.map(Duration::from_micros),
+            solve: Some(Duration::from_micros(2)),
+            ..Report::new(1, part, outcome)
+        };
+        let rows = [
+            Row { input: "alice.txt".to_string(), report: report(Part::One, Outcome::Solved(142.into()), Some(40)), verdict: Some(Verdict::Match) },
+            Row { input: "alice.txt".to_string(), report: report(Part::Two, Outcome::Solved(281.into()), Some(40)), verdict: Some(Verdict::Mismatch { expected: "280".to_string() }) },
+            Row { input: "bob.txt".to_string(), report: report(Part::One, Outcome::Failed("bad input".to_string()), None), verdict: None },
+        ];
+
+        assert_eq!(render(&rows, true), "\
+input      part  answer            parse   solve   check
+alice.txt  1     142               40.0µs  2.00µs  ok
+alice.txt  2     281                       2.00µs  MISMATCH (expected 280)
+bob.txt    1     error: bad input          2.00µs
+");
+        assert!(render(&rows, false).starts_with("input      part  answer            parse   solve\n"));
+        assert_eq!(rows.iter().filter(|row| row.failed()).count(), 2);
+    }
+
+    #[test]
+    fn lists_inputs_without_the_answer_book() {
+        let dir = std::env::temp_dir().join(format!("aoc-batch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["bob.txt", "alice.txt", ".hidden", ANSWERS_FILE] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let names: Vec<String> = input_files(&dir).unwrap().iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["alice.txt", "bob.txt"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
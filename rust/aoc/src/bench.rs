@@ -0,0 +1,116 @@
+use std::{any::Any, fmt};
+
+use aoc_common::{timing::{self, Stats}, Error, Part};
+
+use crate::{cli::BenchOptions, registry::Entry, run::Harness};
+
+fn print_stats(label: &str, stats: Option<Stats>) {
+    if let Some(stats) = stats {
+        println!("{} min {}  median {}  p95 {}  ({} runs)", label,
+            timing::format_duration(stats.min),
+            timing::format_duration(stats.median),
+            timing::format_duration(stats.p95),
+            stats.runs);
+    }
+}
+
+// Why a benchmarked step gave no timings.
+#[derive(Debug, PartialEq)]
+enum Stopped {
+    NotImplemented,
+    Failed(String),
+}
+
+impl fmt::Display for Stopped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stopped::NotImplemented  => write!(f, "{}", Error::NotImplemented),
+            Stopped::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+// One run of `f` within the harness, so that a panic or a run past the
+// budget stops this step rather than the whole benchmark.
+fn attempt<T>(harness: &Harness, f: impl FnOnce() -> aoc_common::Result<T>) -> Result<T, Stopped> {
+    match harness.attempt(f).0 {
+        Ok(Ok(value))                  => Ok(value),
+        Ok(Err(Error::NotImplemented)) => Err(Stopped::NotImplemented),
+        Ok(Err(err))                   => Err(Stopped::Failed(err.to_string())),
+        Err(panic)                     => Err(Stopped::Failed(panic)),
+    }
+}
+
+// Benchmarks the parse step and then every requested part on the parsed
+// input, each run within the `harness`. Returns false if anything failed.
+pub fn bench_day(entry: &Entry, contents: &str, parts: &[Part], options: &BenchOptions, harness: &Harness) -> bool {
+    let label = |what: String| format!("Day {}, {}:", entry.day, what);
+    let parse = || attempt(harness, || entry.solver.parse(contents));
+
+    match timing::bench(options.warmup, options.iterations, parse) {
+        Ok(stats)    => print_stats(&label("Parse".to_string()), stats),
+        Err(stopped) => {
+            println!("{} error: {}", label("Parse".to_string()), stopped);
+            return false;
+        },
+    }
+
+    let input: Box<dyn Any> = match parse() {
+        Ok(input)    => input,
+        Err(stopped) => {
+            println!("{} error: {}", label("Parse".to_string()), stopped);
+            return false;
+        },
+    };
+
+    let mut ok = true;
+    for &part in parts {
+        let label = label(format!("Part {}", part));
+        match timing::bench(options.warmup, options.iterations, || attempt(harness, || entry.solver.solve(part, input.as_ref()))) {
+            Ok(stats)                    => print_stats(&label, stats),
+            Err(Stopped::NotImplemented) => println!("{} Not Done Yet!", label),
+            Err(stopped)                 => {
+                println!("{} error: {}", label, stopped);
+                ok = false;
+            },
+        }
+    }
+    ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{budget::{self, Budget}, Answer, Solution};
+
+    struct Broken;
+
+    impl Solution for Broken {
+        type Input = ();
+
+        fn parse(&self, _contents: &str) -> aoc_common::Result<()> {
+            Ok(())
+        }
+
+        fn part_1(&self, _input: &()) -> aoc_common::Result<Answer> {
+            panic!("index out of bounds")
+        }
+
+        fn part_2(&self, _input: &()) -> aoc_common::Result<Answer> {
+            loop {
+                budget::step()?;
+            }
+        }
+    }
+
+    #[test]
+    fn panics_and_endless_parts_fail_on_their_own() {
+        let harness = Harness { budget: Budget::unlimited().steps(1_000), ..Harness::default() };
+        let options = BenchOptions { warmup: 1, iterations: 2 };
+        let entry = Entry { day: 99, solver: &Broken };
+
+        assert_eq!(attempt(&harness, || entry.solver.solve(Part::One, &())), Err(Stopped::Failed("panicked: index out of bounds".to_string())));
+        assert!(matches!(attempt(&harness, || entry.solver.solve(Part::Two, &())), Err(Stopped::Failed(message)) if message.starts_with("budget exceeded")));
+        assert!(!bench_day(&entry, "", &Part::ALL, &options, &harness));
+    }
+}
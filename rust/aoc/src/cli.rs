@@ -0,0 +1,627 @@
+use std::time::Duration;
+
+use aoc_common::{budget::Budget, trace::Level, Part};
+
+use crate::run::Harness;
+
+pub const USAGE: &str = "\
+usage:
+    aoc run --day <N> [--part <1|2>] [--input <path|->] [--input-dir <dir>] [--format <text|json|csv>] [--stream] [--timeout <time>] [-v|-vv]
+    aoc run --all [--jobs <N>] [--input-dir <dir>] [--format <text|json|csv>] [--timeout <time>] [-v|-vv]
+    aoc bench (--day <N> | --all) [--part <1|2>] [--warmup <N>] [--iterations <N>] [--timeout <time>]
+    aoc verify [--day <N> | --all] [--answers <path>] [--record] [--overwrite] [--timeout <time>] [-v|-vv]
+    aoc batch --day <N> --inputs <dir> [--part <1|2>] [--check] [--answers <path>] [--timeout <time>]
+    aoc diff --day <N> [--part <1|2>] [--input <path|->] [--timeout <time>]
+    aoc generate --day <N> [--seed <N>] [--size <N>] [--output <path>] [--record]
+    aoc fuzz (--day <N> | --all) [--runs <N>] [--seed <N>]
+    aoc fetch (--day <N> | --all) [--input-dir <dir>] [--base-url <url>]
+    aoc puzzle --day <N> [--base-url <url>]
+    aoc submit --day <N> [--part <1|2>] [--answer <answer>] [--input <path|->] [--base-url <url>] [--timeout <time>] [-v|-vv]
+    aoc mock-server [--port <N>] [--input-dir <dir>] [--answers <path>]
+    aoc new <day>
+    aoc list
+
+Inputs are read from --input (`-` for stdin), or else from <dir>/dayNN.txt
+where <dir> is --input-dir, $AOC_INPUT_DIR or ./inputs, in that order.
+They are normalized first (BOM, CRLF, trailing blank lines, final newline);
+--show-normalization reports what that changed.
+Known answers live in <dir>/answers.txt unless --answers says otherwise.
+`verify --record` adds answers the book does not know yet; --overwrite also
+replaces the ones that differ.
+`batch` solves every file in --inputs and prints a table; --check (or
+--answers) compares each answer with the known ones.
+`diff` runs every implementation of a part that has several on the same
+input, reporting their timings and any answers that differ.
+`generate` prints a synthetic input (seed 1 and size 100 by default) and
+its expected answers on stderr; --record adds those to the answer book.
+`fuzz` feeds mutated examples to a day's parser and solver (10000 by
+default) and saves the first input that panics to fuzz/corpus/dayNN.
+--timeout stops a part that runs longer than <time> (e.g. 30, 1.5s, 500ms, 2m);
+in `bench` it bounds every single run.
+-v prints the events and counters solvers trace to stderr; -vv adds the
+per-step ones.
+
+The puzzle site and session token come from --base-url, $AOC_BASE_URL and
+$AOC_SESSION, or the `base_url` and `session` keys of the config file at
+$AOC_CONFIG (~/.config/aoc/config by default). Fetched inputs are saved to
+<dir>/dayNN.txt and never downloaded again.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions, RunSettings),
+    Bench(RunOptions, BenchOptions),
+    Verify(RunOptions, VerifyOptions),
+    Batch(RunOptions, BatchOptions),
+    Diff(RunOptions),
+    Generate(RunOptions, GenerateOptions),
+    Fuzz(RunOptions, FuzzOptions),
+    Fetch(RunOptions, SiteOptions),
+    Puzzle(RunOptions, SiteOptions),
+    Submit(RunOptions, SiteOptions),
+    Mock(MockOptions),
+    New(u8),
+    List,
+    Help,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RunOptions {
+    pub day: Option<u8>,
+    pub part: Option<Part>,
+    pub input: Option<String>,
+    pub input_dir: Option<String>,
+    pub all: bool,
+    pub show_normalization: bool,
+    pub timeout: Option<Duration>,
+    pub verbosity: u8,
+}
+
+impl RunOptions {
+    pub fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None       => Part::ALL.to_vec(),
+        }
+    }
+
+    // What each parse and each part may spend before it is stopped, and how
+    // much of what it traces to keep.
+    pub fn harness(&self) -> Harness {
+        let budget = match self.timeout {
+            Some(timeout) => Budget::unlimited().time(timeout),
+            None          => Budget::unlimited(),
+        };
+        Harness { budget, trace: Level::from_verbosity(self.verbosity) }
+    }
+}
+
+// How `run` prints its results: for people, or as JSON or CSV for tools.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+// Settings of `run` alone: the output format, how many solvers may run at
+// once and whether to solve from a reader instead of a loaded string.
+#[derive(Debug, PartialEq)]
+pub struct RunSettings {
+    pub format: Format,
+    pub jobs: usize,
+    pub stream: bool,
+}
+
+impl Default for RunSettings {
+    fn default() -> Self {
+        Self { format: Format::Text, jobs: 1, stream: false }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self { warmup: 3, iterations: 20 }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct VerifyOptions {
+    pub answers: Option<String>,
+    pub record: bool,
+    // Replace stored answers that differ; implies `record`.
+    pub overwrite: bool,
+}
+
+// Where `batch` finds its inputs and whether to check their answers.
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchOptions {
+    pub inputs: String,
+    pub answers: Option<String>,
+    pub check: bool,
+}
+
+// Which synthetic input `generate` makes and where it goes.
+#[derive(Debug, PartialEq)]
+pub struct GenerateOptions {
+    pub seed: u64,
+    pub size: usize,
+    pub output: Option<String>,
+    pub record: bool,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self { seed: 1, size: 100, output: None, record: false }
+    }
+}
+
+// How long `fuzz` runs and from which seed; a fresh one every run unless set.
+#[derive(Debug, PartialEq)]
+pub struct FuzzOptions {
+    pub runs: usize,
+    pub seed: Option<u64>,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        Self { runs: 10_000, seed: None }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SiteOptions {
+    pub base_url: Option<String>,
+    pub answer: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MockOptions {
+    pub port: u16,
+    pub input_dir: Option<String>,
+    pub answers: Option<String>,
+}
+
+impl Default for MockOptions {
+    fn default() -> Self {
+        Self { port: 8023, input_dir: None, answers: None }
+    }
+}
+
+// Command-specific settings accepted on top of the shared selection flags.
+enum Settings<'a> {
+    Run(&'a mut RunSettings),
+    Bench(&'a mut BenchOptions),
+    Verify(&'a mut VerifyOptions),
+    Batch(&'a mut BatchOptions),
+    Diff,
+    Generate(&'a mut GenerateOptions),
+    Fuzz(&'a mut FuzzOptions),
+    Site(&'a mut SiteOptions),
+    Submit(&'a mut SiteOptions),
+}
+
+fn value<'a>(flag: &str, args: &mut impl Iterator<Item = &'a String>) -> Result<&'a str, String> {
+    args.next().map(|s| s.as_str()).ok_or_else(|| format!("{} expects a value", flag))
+}
+
+fn count(flag: &str, value: &str) -> Result<usize, String> {
+    value.parse().map_err(|_| format!("{} expects a count, got '{}'", flag, value))
+}
+
+// Seconds, plain or with an `ms`, `s` or `m` suffix.
+fn duration(flag: &str, value: &str) -> Result<Duration, String> {
+    let (number, scale) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1.0)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 60.0)
+    } else {
+        (value, 1.0)
+    };
+    number.parse::<f64>().ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * scale).ok())
+        .filter(|duration| !duration.is_zero())
+        .ok_or_else(|| format!("{} expects a positive time, got '{}'", flag, value))
+}
+
+// Parses the day/part/input selection shared by every command that runs
+// solvers, plus the flags specific to `settings`.
+fn parse_run(command: &str, args: &[String], mut settings: Settings) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), &mut settings) {
+            ("--day" | "-d", _)                             => {
+                let day = value(arg, &mut args)?;
+                options.day = Some(day.parse().map_err(|_| format!("invalid day '{}'", day))?);
+            },
+            ("--part" | "-p", _)                            => {
+                options.part = match value(arg, &mut args)? {
+                    "1"   => Some(Part::One),
+                    "2"   => Some(Part::Two),
+                    other => return Err(format!("invalid part '{}', expected 1 or 2", other)),
+                };
+            },
+            ("--input" | "-i", _)                           => options.input = Some(value(arg, &mut args)?.to_string()),
+            ("--input-dir", _)                              => options.input_dir = Some(value(arg, &mut args)?.to_string()),
+            ("--all" | "-a", _)                             => options.all = true,
+            ("--show-normalization", _)                     => options.show_normalization = true,
+            ("--format" | "-f", Settings::Run(run))         => {
+                run.format = match value(arg, &mut args)? {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    "csv"  => Format::Csv,
+                    other  => return Err(format!("invalid format '{}', expected text, json or csv", other)),
+                };
+            },
+            ("--jobs" | "-j", Settings::Run(run))           => {
+                run.jobs = count(arg, value(arg, &mut args)?)?;
+                if run.jobs == 0 {
+                    return Err(format!("{} must be at least 1", arg));
+                }
+            },
+            ("--stream", Settings::Run(run))                => run.stream = true,
+            ("--timeout", Settings::Run(_) | Settings::Bench(_) | Settings::Verify(_) | Settings::Batch(_) | Settings::Diff | Settings::Submit(_))
+                                                            => options.timeout = Some(duration(arg, value(arg, &mut args)?)?),
+            ("-v" | "-vv" | "--verbose", Settings::Run(_) | Settings::Verify(_) | Settings::Batch(_) | Settings::Submit(_))
+                                                            => options.verbosity += if arg == "-vv" { 2 } else { 1 },
+            ("--warmup", Settings::Bench(bench))            => bench.warmup = count(arg, value(arg, &mut args)?)?,
+            ("--iterations" | "-n", Settings::Bench(bench)) => {
+                bench.iterations = count(arg, value(arg, &mut args)?)?;
+                if bench.iterations == 0 {
+                    return Err(format!("{} must be at least 1", arg));
+                }
+            },
+            ("--answers", Settings::Verify(verify))         => verify.answers = Some(value(arg, &mut args)?.to_string()),
+            ("--record", Settings::Verify(verify))          => verify.record = true,
+            ("--overwrite", Settings::Verify(verify))       => (verify.record, verify.overwrite) = (true, true),
+            ("--inputs", Settings::Batch(batch))            => batch.inputs = value(arg, &mut args)?.to_string(),
+            ("--check", Settings::Batch(batch))             => batch.check = true,
+            ("--seed", Settings::Generate(generate))        => {
+                let seed = value(arg, &mut args)?;
+                generate.seed = seed.parse().map_err(|_| format!("invalid seed '{}'", seed))?;
+            },
+            ("--size", Settings::Generate(generate))        => generate.size = count(arg, value(arg, &mut args)?)?,
+            ("--output" | "-o", Settings::Generate(generate))
+                                                            => generate.output = Some(value(arg, &mut args)?.to_string()),
+            ("--record", Settings::Generate(generate))      => generate.record = true,
+            ("--runs", Settings::Fuzz(fuzz))                => fuzz.runs = count(arg, value(arg, &mut args)?)?,
+            ("--seed", Settings::Fuzz(fuzz))                => {
+                let seed = value(arg, &mut args)?;
+                fuzz.seed = Some(seed.parse().map_err(|_| format!("invalid seed '{}'", seed))?);
+            },
+            ("--answers", Settings::Batch(batch))           => {
+                batch.answers = Some(value(arg, &mut args)?.to_string());
+                batch.check = true;
+            },
+            ("--base-url", Settings::Site(site) | Settings::Submit(site))
+                                                            => site.base_url = Some(value(arg, &mut args)?.to_string()),
+            ("--answer", Settings::Submit(site))            => site.answer = Some(value(arg, &mut args)?.to_string()),
+            (other, _)                                      => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    // Verifying defaults to every day, since that is what a regression check wants.
+    if let (Settings::Verify(_), None) = (&settings, options.day) {
+        options.all = true;
+    }
+
+    if let Settings::Run(RunSettings { stream: true, jobs, .. }) = &settings {
+        if options.all {
+            return Err("--stream works on a single --day".to_string());
+        }
+        if *jobs > 1 {
+            return Err("--stream solves on one thread; drop --jobs".to_string());
+        }
+        // Stdin can only be read once, and every part needs its own pass.
+        if options.input.as_deref() == Some("-") && options.part.is_none() {
+            return Err("--stream from stdin needs --part".to_string());
+        }
+    }
+
+    if let Settings::Batch(batch) = &settings {
+        if batch.inputs.is_empty() {
+            return Err("batch needs --inputs <dir>".to_string());
+        }
+        if options.all || options.input.is_some() {
+            return Err("batch runs a single --day on the files in --inputs".to_string());
+        }
+    }
+
+    if let (Settings::Generate(_), true) = (&settings, options.all || options.input.is_some()) {
+        return Err("generate makes one --day's input at a time".to_string());
+    }
+
+    if let (Settings::Fuzz(_), Some(_)) = (&settings, &options.input) {
+        return Err("fuzz makes its own inputs from the corpus".to_string());
+    }
+
+    if let (Settings::Submit(site), None) = (&settings, options.part) {
+        if site.answer.is_some() {
+            return Err("--answer needs --part".to_string());
+        }
+    }
+
+    match (options.day, options.all) {
+        (Some(_), true)  => Err("--day and --all are mutually exclusive".to_string()),
+        (None, false)    => Err(format!("{} needs either --day <N> or --all", command)),
+        _ if options.all && options.input.is_some()
+                         => Err("--input can only be used with --day".to_string()),
+        _                => Ok(options),
+    }
+}
+
+fn parse_mock(args: &[String]) -> Result<MockOptions, String> {
+    let mut options = MockOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port"      => {
+                let port = value(arg, &mut args)?;
+                options.port = port.parse().map_err(|_| format!("invalid port '{}'", port))?;
+            },
+            "--input-dir" => options.input_dir = Some(value(arg, &mut args)?.to_string()),
+            "--answers"   => options.answers = Some(value(arg, &mut args)?.to_string()),
+            other         => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    Ok(options)
+}
+
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    match args.first().map(|s| s.as_str()) {
+        Some("run")                                => {
+            let mut run = RunSettings::default();
+            let options = parse_run("run", &args[1..], Settings::Run(&mut run))?;
+            Ok(Command::Run(options, run))
+        },
+        Some("bench")                              => {
+            let mut bench = BenchOptions::default();
+            let options = parse_run("bench", &args[1..], Settings::Bench(&mut bench))?;
+            Ok(Command::Bench(options, bench))
+        },
+        Some("verify")                             => {
+            let mut verify = VerifyOptions::default();
+            let options = parse_run("verify", &args[1..], Settings::Verify(&mut verify))?;
+            Ok(Command::Verify(options, verify))
+        },
+        Some("batch")                              => {
+            let mut batch = BatchOptions::default();
+            let options = parse_run("batch", &args[1..], Settings::Batch(&mut batch))?;
+            Ok(Command::Batch(options, batch))
+        },
+        Some("diff")                               => {
+            let options = parse_run("diff", &args[1..], Settings::Diff)?;
+            if options.all {
+                return Err("diff works on a single --day".to_string());
+            }
+            Ok(Command::Diff(options))
+        },
+        Some("generate")                           => {
+            let mut generate = GenerateOptions::default();
+            let options = parse_run("generate", &args[1..], Settings::Generate(&mut generate))?;
+            Ok(Command::Generate(options, generate))
+        },
+        Some("fuzz")                               => {
+            let mut fuzz = FuzzOptions::default();
+            let options = parse_run("fuzz", &args[1..], Settings::Fuzz(&mut fuzz))?;
+            Ok(Command::Fuzz(options, fuzz))
+        },
+        Some("fetch")                              => {
+            let mut site = SiteOptions::default();
+            let options = parse_run("fetch", &args[1..], Settings::Site(&mut site))?;
+            Ok(Command::Fetch(options, site))
+        },
+        Some(command @ ("puzzle" | "submit"))      => {
+            let mut site = SiteOptions::default();
+            let options = match command {
+                "puzzle" => parse_run(command, &args[1..], Settings::Site(&mut site))?,
+                _        => parse_run(command, &args[1..], Settings::Submit(&mut site))?,
+            };
+            if options.all {
+                return Err(format!("{} works on a single --day", command));
+            }
+            Ok(match command {
+                "puzzle" => Command::Puzzle(options, site),
+                _        => Command::Submit(options, site),
+            })
+        },
+        Some("mock-server")                        => Ok(Command::Mock(parse_mock(&args[1..])?)),
+        Some("new")                                => match &args[1..] {
+            [day] => Ok(Command::New(day.parse().map_err(|_| format!("invalid day '{}'", day))?)),
+            _     => Err("new expects exactly one day number".to_string()),
+        },
+        Some("list")                               => Ok(Command::List),
+        Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some(other)                                => Err(format!("unknown command '{}'", other)),
+        None                                       => Err("missing command".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_single_day() {
+        let command = parse(&args("run --day 7 --part 2 --input path.txt")).unwrap();
+        assert_eq!(command, Command::Run(RunOptions {
+            day: Some(7),
+            part: Some(Part::Two),
+            input: Some("path.txt".to_string()),
+            input_dir: None,
+            all: false,
+            show_normalization: false,
+            timeout: None,
+            verbosity: 0,
+        }, RunSettings::default()));
+    }
+
+    #[test]
+    fn parses_output_format() {
+        let command = parse(&args("run --all --format json")).unwrap();
+        assert_eq!(command, Command::Run(
+            RunOptions { all: true, ..RunOptions::default() },
+            RunSettings { format: Format::Json, ..RunSettings::default() },
+        ));
+        assert!(parse(&args("run --all --format yaml")).is_err());
+        assert!(parse(&args("bench --all --format csv")).is_err());
+    }
+
+    #[test]
+    fn parses_job_count() {
+        let command = parse(&args("run --all -j 4")).unwrap();
+        assert_eq!(command, Command::Run(
+            RunOptions { all: true, ..RunOptions::default() },
+            RunSettings { jobs: 4, ..RunSettings::default() },
+        ));
+        assert!(parse(&args("run --all --jobs 0")).is_err());
+        assert!(parse(&args("bench --all --jobs 2")).is_err());
+    }
+
+    #[test]
+    fn parses_timeouts() {
+        for (timeout, expected) in [("30", 30_000), ("1.5", 1_500), ("500ms", 500), ("2s", 2_000), ("1m", 60_000)] {
+            let Command::Run(options, _) = parse(&args(&format!("run --all --timeout {}", timeout))).unwrap() else { panic!() };
+            assert_eq!(options.timeout, Some(Duration::from_millis(expected)));
+        }
+        assert!(parse(&args("run --all --timeout 0")).is_err());
+        assert!(parse(&args("run --all --timeout soon")).is_err());
+        assert!(parse(&args("fetch --all --timeout 1")).is_err());
+    }
+
+    #[test]
+    fn verbosity_picks_a_trace_level() {
+        let trace = |line: &str| parse(&args(line)).map(|command| match command {
+            Command::Run(options, _) | Command::Verify(options, _) => options.harness().trace,
+            _                                                      => unreachable!(),
+        });
+        assert_eq!(trace("run --all"), Ok(None));
+        assert_eq!(trace("run --all -v"), Ok(Some(Level::Info)));
+        assert_eq!(trace("verify -vv"), Ok(Some(Level::Debug)));
+        assert_eq!(trace("run --all -v --verbose"), Ok(Some(Level::Debug)));
+        assert!(parse(&args("bench --all -v")).is_err());
+    }
+
+    #[test]
+    fn streaming_needs_a_single_day() {
+        let command = parse(&args("run --day 9 --part 1 --input - --stream")).unwrap();
+        assert_eq!(command, Command::Run(
+            RunOptions { day: Some(9), part: Some(Part::One), input: Some("-".to_string()), ..RunOptions::default() },
+            RunSettings { stream: true, ..RunSettings::default() },
+        ));
+        assert!(parse(&args("run --all --stream")).is_err());
+        assert!(parse(&args("run --day 9 --input - --stream")).is_err());
+        assert!(parse(&args("run --day 9 --stream --jobs 2")).is_err());
+    }
+
+    #[test]
+    fn rejects_conflicting_selection() {
+        assert!(parse(&args("run --day 7 --all")).is_err());
+        assert!(parse(&args("run")).is_err());
+        assert!(parse(&args("run --all --input x")).is_err());
+        assert!(parse(&args("run --day 7 --part 3")).is_err());
+    }
+
+    #[test]
+    fn batch_needs_a_day_and_a_directory() {
+        let command = parse(&args("batch --day 1 --inputs team/ --answers team/answers.txt")).unwrap();
+        assert_eq!(command, Command::Batch(
+            RunOptions { day: Some(1), ..RunOptions::default() },
+            BatchOptions { inputs: "team/".to_string(), answers: Some("team/answers.txt".to_string()), check: true },
+        ));
+        assert!(parse(&args("batch --day 1")).is_err());
+        assert!(parse(&args("batch --all --inputs team/")).is_err());
+        assert!(parse(&args("batch --day 1 --inputs team/ --input day01.txt")).is_err());
+        assert!(parse(&args("run --day 1 --inputs team/")).is_err());
+    }
+
+    #[test]
+    fn diff_compares_a_single_day() {
+        let command = parse(&args("diff --day 5 --part 2 --timeout 10")).unwrap();
+        assert_eq!(command, Command::Diff(RunOptions {
+            day: Some(5),
+            part: Some(Part::Two),
+            timeout: Some(Duration::from_secs(10)),
+            ..RunOptions::default()
+        }));
+        assert!(parse(&args("diff --all")).is_err());
+        assert!(parse(&args("diff --day 5 --format json")).is_err());
+    }
+
+    #[test]
+    fn parses_generator_settings() {
+        let command = parse(&args("generate --day 8 --seed 42 --size 5000 -o big.txt")).unwrap();
+        assert_eq!(command, Command::Generate(
+            RunOptions { day: Some(8), ..RunOptions::default() },
+            GenerateOptions { seed: 42, size: 5000, output: Some("big.txt".to_string()), record: false },
+        ));
+        assert_eq!(parse(&args("generate --day 8")).unwrap(), Command::Generate(
+            RunOptions { day: Some(8), ..RunOptions::default() },
+            GenerateOptions::default(),
+        ));
+        assert!(parse(&args("generate --all")).is_err());
+        assert!(parse(&args("generate --day 8 --seed x")).is_err());
+    }
+
+    #[test]
+    fn parses_fuzz_settings() {
+        let command = parse(&args("fuzz --all --runs 500 --seed 7")).unwrap();
+        assert_eq!(command, Command::Fuzz(
+            RunOptions { all: true, ..RunOptions::default() },
+            FuzzOptions { runs: 500, seed: Some(7) },
+        ));
+        assert!(parse(&args("fuzz --day 3 --input crash.txt")).is_err());
+        assert!(parse(&args("run --day 3 --runs 5")).is_err());
+    }
+
+    #[test]
+    fn bench_settings_only_apply_to_bench() {
+        let command = parse(&args("bench --all --warmup 1 -n 5")).unwrap();
+        assert_eq!(command, Command::Bench(
+            RunOptions { all: true, ..RunOptions::default() },
+            BenchOptions { warmup: 1, iterations: 5 },
+        ));
+        assert!(parse(&args("run --all --warmup 1")).is_err());
+        assert!(parse(&args("bench --all -n 0")).is_err());
+    }
+
+    #[test]
+    fn verify_defaults_to_all_days() {
+        let command = parse(&args("verify --record")).unwrap();
+        assert_eq!(command, Command::Verify(
+            RunOptions { all: true, ..RunOptions::default() },
+            VerifyOptions { answers: None, record: true, overwrite: false },
+        ));
+        let Command::Verify(_, verify) = parse(&args("verify --day 3 --overwrite")).unwrap() else { panic!() };
+        assert_eq!(verify, VerifyOptions { answers: None, record: true, overwrite: true });
+        assert!(parse(&args("verify --day 3 --all")).is_err());
+    }
+
+    #[test]
+    fn site_commands_take_a_base_url() {
+        let command = parse(&args("submit --day 1 --part 2 --answer 281 --base-url http://127.0.0.1:8023")).unwrap();
+        assert_eq!(command, Command::Submit(
+            RunOptions { day: Some(1), part: Some(Part::Two), ..RunOptions::default() },
+            SiteOptions { base_url: Some("http://127.0.0.1:8023".to_string()), answer: Some("281".to_string()) },
+        ));
+        assert!(parse(&args("submit --day 1 --answer 281")).is_err());
+        assert!(parse(&args("puzzle --all")).is_err());
+        assert!(parse(&args("fetch --all --answer 1")).is_err());
+        assert_eq!(parse(&args("mock-server --port 0")).unwrap(), Command::Mock(MockOptions { port: 0, ..MockOptions::default() }));
+    }
+}
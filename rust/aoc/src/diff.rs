@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use aoc_common::{timing, Answer, Error, Part};
+
+use crate::{
+    batch,
+    registry::Entry,
+    run::{Harness, Outcome},
+};
+
+// How one implementation did on the shared input.
+#[derive(Debug)]
+pub struct Trial {
+    pub name: &'static str,
+    pub outcome: Outcome,
+    pub solve: Duration,
+}
+
+// Every implementation of one part, run on the same parsed input.
+#[derive(Debug)]
+pub struct Comparison {
+    pub part: Part,
+    pub trials: Vec<Trial>,
+}
+
+impl Comparison {
+    fn answers(&self) -> impl Iterator<Item = &Answer> {
+        self.trials.iter().filter_map(|trial| match &trial.outcome {
+            Outcome::Solved(answer) => Some(answer),
+            _                       => None,
+        })
+    }
+
+    // Whether two implementations gave different answers; one that failed
+    // disagrees with nothing.
+    pub fn disagrees(&self) -> bool {
+        let mut answers = self.answers();
+        answers.next().is_some_and(|first| answers.any(|answer| answer != first))
+    }
+
+    pub fn failed(&self) -> bool {
+        self.trials.iter().any(|trial| matches!(trial.outcome, Outcome::Failed(_)))
+    }
+}
+
+// Parses the input once and runs every implementation of each part that has
+// several on it, each within the `harness`. Parts with a single
+// implementation are left out.
+pub fn diff_day(entry: &Entry, contents: &str, parts: &[Part], harness: &Harness) -> Result<Vec<Comparison>, String> {
+    let input = match harness.attempt(|| entry.solver.parse(contents)).0 {
+        Ok(Ok(input)) => input,
+        Ok(Err(err))  => return Err(err.to_string()),
+        Err(panic)    => return Err(panic),
+    };
+
+    let comparisons = parts.iter()
+        .map(|&part| {
+            let trials = entry.solver.implementations(part).into_iter()
+                .map(|name| {
+                    let ((result, _), solve) = timing::time(|| harness.attempt(|| entry.solver.solve_with(part, name, input.as_ref())));
+                    let outcome = match result {
+                        Ok(Ok(answer))                 => Outcome::Solved(answer),
+                        Ok(Err(Error::NotImplemented)) => Outcome::NotImplemented,
+                        Ok(Err(err))                   => Outcome::Failed(err.to_string()),
+                        Err(panic)                     => Outcome::Failed(panic),
+                    };
+                    Trial { name, outcome, solve }
+                })
+                .collect();
+            Comparison { part, trials }
+        })
+        .filter(|comparison| comparison.trials.len() > 1)
+        .collect();
+    Ok(comparisons)
+}
+
+// A time relative to another to three significant digits, like the times.
+fn ratio(ratio: f64) -> String {
+    let decimals = match ratio {
+        r if r >= 100.0 => 0,
+        r if r >= 10.0  => 1,
+        r if r >= 1.0   => 2,
+        r               => (2 - r.log10().floor() as i32) as usize,
+    };
+    format!("{:.*}x", decimals, ratio)
+}
+
+// A table with one line per implementation: its answer, its time and that
+// time relative to the first implementation of the part, the one `run` uses.
+// Answers that differ from the first answer found are flagged.
+pub fn render(comparisons: &[Comparison]) -> String {
+    let header = ["part", "implementation", "answer", "solve", "relative", ""];
+    let mut table: Vec<Vec<String>> = vec![header.into_iter().map(String::from).collect()];
+
+    for comparison in comparisons {
+        let baseline = comparison.trials[0].solve.as_secs_f64();
+        let first = comparison.answers().next();
+        for trial in &comparison.trials {
+            let (answer, differs) = match &trial.outcome {
+                Outcome::Solved(answer)  => (answer.to_string(), Some(answer) != first),
+                Outcome::NotImplemented  => ("not done yet".to_string(), false),
+                Outcome::Failed(message) => (format!("error: {}", message), false),
+            };
+            table.push(vec![
+                comparison.part.to_string(),
+                trial.name.to_string(),
+                answer,
+                timing::format_duration(trial.solve),
+                if baseline > 0.0 { ratio(trial.solve.as_secs_f64() / baseline) } else { String::new() },
+                if differs { "DIFFERS".to_string() } else { String::new() },
+            ]);
+        }
+    }
+
+    batch::layout(&table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{Implementation, Result, Solution};
+
+    struct Split;
+
+    impl Solution for Split {
+        type Input = ();
+
+        fn parse(&self, _contents: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn implementations(&self) -> Vec<Implementation<()>> {
+            vec![
+                Implementation { name: "one",   part: Part::One, solve: |_| Ok(1.into()) },
+                Implementation { name: "also",  part: Part::One, solve: |_| Ok(1.into()) },
+                Implementation { name: "slow",  part: Part::Two, solve: |_| Ok(2.into()) },
+                Implementation { name: "wrong", part: Part::Two, solve: |_| Ok(3.into()) },
+                Implementation { name: "boom",  part: Part::Two, solve: |_| panic!("boom") },
+            ]
+        }
+    }
+
+    #[test]
+    fn flags_disagreements() {
+        let comparisons = diff_day(&Entry { day: 99, solver: &Split }, "", &Part::ALL, &Harness::default()).unwrap();
+
+        assert_eq!((comparisons[0].disagrees(), comparisons[0].failed()), (false, false));
+        assert_eq!((comparisons[1].disagrees(), comparisons[1].failed()), (true, true));
+        assert!(matches!(&comparisons[1].trials[2].outcome, Outcome::Failed(message) if message == "panicked: boom"));
+    }
+
+    #[test]
+    fn renders_relative_timings() {
+        let trial = |name, answer: i32, micros| Trial { name, outcome: Outcome::Solved(Answer::from(answer)), solve: Duration::from_micros(micros) };
+        let comparisons = [Comparison { part: Part::Two, trials: vec![trial("brute-force", 46, 400), trial("intervals", 46, 4), trial("broken", 45, 100)] }];
+
+        assert_eq!(render(&comparisons), "\
+part  implementation  answer  solve   relative
+2     brute-force     46      400µs   1.00x
+2     intervals       46      4.00µs  0.0100x
+2     broken          45      100µs   0.250x    DIFFERS
+");
+        assert_eq!((ratio(350.0), ratio(12.34), ratio(0.000294)), ("350x".to_string(), "12.3x".to_string(), "0.000294x".to_string()));
+    }
+}
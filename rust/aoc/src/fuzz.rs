@@ -0,0 +1,91 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use aoc_common::{fuzz, generate::Rng};
+
+use crate::registry::Entry;
+
+// Where the inputs worth keeping for a day live, crashes found earlier among
+// them.
+pub fn corpus_dir(root: &Path, day: u8) -> PathBuf {
+    root.join("fuzz").join("corpus").join(format!("day{:02}", day))
+}
+
+// What fuzzing a day starts from: its examples, `dayN/src/test*.dat`, and
+// whatever its corpus directory holds, in name order.
+pub fn corpus(root: &Path, day: u8) -> Vec<(PathBuf, Vec<u8>)> {
+    let examples = fs::read_dir(root.join(format!("day{}", day)).join("src")).into_iter().flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("test") && name.ends_with(".dat")));
+    let kept = fs::read_dir(corpus_dir(root, day)).into_iter().flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file());
+
+    let mut paths: Vec<PathBuf> = examples.chain(kept).collect();
+    paths.sort();
+    paths.into_iter()
+        .filter_map(|path| fs::read(&path).ok().map(|data| (path, data)))
+        .collect()
+}
+
+// An input that made the day panic, and the panic's message.
+#[derive(Debug)]
+pub struct Crash {
+    pub input: Vec<u8>,
+    pub message: String,
+}
+
+// Runs every `corpus` entry through the day's fuzz target, then `runs`
+// mutants of them drawn from `seed`, stopping at the first panic.
+pub fn fuzz_day(entry: &Entry, corpus: &[Vec<u8>], runs: usize, seed: u64) -> Option<Crash> {
+    let mut rng = Rng::new(seed);
+    let mutants = (0..runs).filter(|_| !corpus.is_empty())
+        .map(|_| {
+            let input = rng.pick(corpus);
+            fuzz::mutate(&mut rng, input, corpus)
+        });
+
+    corpus.iter().cloned().chain(mutants)
+        .find_map(|input| fuzz::target(entry.solver, &input).err().map(|message| Crash { input, message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+    }
+
+    #[test]
+    fn the_corpus_starts_from_the_examples() {
+        let names: Vec<String> = corpus(&root(), 8).iter()
+            .filter_map(|(path, _)| path.strip_prefix(root().join("day8")).ok().map(|path| path.display().to_string()))
+            .collect();
+        assert_eq!(names, ["src/test-1.dat", "src/test-2.dat", "src/test-3.dat"]);
+    }
+
+    #[test]
+    fn nothing_in_the_corpus_panics() {
+        for entry in registry::DAYS {
+            for (path, input) in corpus(&root(), entry.day) {
+                assert_eq!(fuzz::target(entry.solver, &input), Ok(()), "{}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn a_short_fuzz_finds_no_panics() {
+        for entry in registry::DAYS {
+            let corpus: Vec<Vec<u8>> = corpus(&root(), entry.day).into_iter().map(|(_, input)| input).collect();
+            let crash = fuzz_day(entry, &corpus, 200, entry.day as u64);
+            assert!(crash.is_none(), "day {}: {:?}", entry.day, crash.map(|crash| (crash.message, String::from_utf8_lossy(&crash.input).into_owned())));
+        }
+    }
+}
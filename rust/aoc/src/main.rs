@@ -0,0 +1,531 @@
+mod batch;
+mod bench;
+mod cli;
+mod diff;
+mod fuzz;
+mod pool;
+mod registry;
+mod run;
+mod scaffold;
+mod site;
+mod verify;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use aoc_client::mock::MockServer;
+use aoc_common::{answers::{fingerprint, AnswerBook, ANSWERS_FILE}, input, Error, InputSource, Part};
+
+use cli::{BatchOptions, BenchOptions, Command, FuzzOptions, GenerateOptions, Format, MockOptions, RunOptions, RunSettings, SiteOptions, VerifyOptions};
+use registry::Entry;
+use run::{Harness, Outcome, Report};
+
+// Reads and normalizes an input, noting on stderr what normalizing changed
+// when `show` is set.
+fn load(source: &InputSource, show: bool) -> Result<String, String> {
+    let (contents, changes) = source.read_normalized().map_err(|err| match (&err, source) {
+        (Error::Io { source: io, .. }, InputSource::Path(_)) if io.kind() == std::io::ErrorKind::NotFound
+              => format!("{} (pass --input or set {})", err, input::INPUT_DIR_VAR),
+        _     => err.to_string(),
+    })?;
+
+    if show && !changes.is_empty() {
+        eprintln!("note: {}: {}", source, changes);
+    }
+    Ok(contents)
+}
+
+fn select(options: &RunOptions) -> Result<Vec<&'static Entry>, ExitCode> {
+    match options.day {
+        Some(day) => match registry::find(day) {
+            Some(entry) => Ok(vec![entry]),
+            None        => {
+                eprintln!("error: day {} is not registered", day);
+                Err(ExitCode::from(2))
+            },
+        },
+        None      => Ok(registry::DAYS.iter().collect()),
+    }
+}
+
+// Loads the input of every selected day and hands it to `f`, which reports
+// whether the day succeeded.
+fn for_each_day(options: &RunOptions, mut f: impl FnMut(&Entry, Result<String, String>) -> bool) -> ExitCode {
+    let entries = match select(options) {
+        Ok(entries) => entries,
+        Err(code)   => return code,
+    };
+    let dir = input::input_dir(options.input_dir.as_deref());
+    let mut failed = false;
+
+    for entry in entries {
+        let source = input::resolve(entry.day, options.input.as_deref(), &dir);
+        failed |= !f(entry, load(&source, options.show_normalization));
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn solve(entry: &Entry, contents: &Result<String, String>, parts: &[Part], harness: &Harness) -> Vec<Report> {
+    match contents {
+        Ok(contents) => run::run_day(entry, contents, parts, harness),
+        Err(message) => parts.iter()
+            .map(|&part| Report::new(entry.day, part, Outcome::Failed(message.clone())))
+            .collect(),
+    }
+}
+
+// Solves every selected day as a job of its own on `jobs` threads, parsing
+// its input once for all its parts, then hands the reports to `emit` one day
+// at a time, in day order.
+fn run_parallel(options: &RunOptions, jobs: usize, mut emit: impl FnMut(Vec<Report>) -> bool) -> ExitCode {
+    let entries = match select(options) {
+        Ok(entries) => entries,
+        Err(code)   => return code,
+    };
+    let dir = input::input_dir(options.input_dir.as_deref());
+    let parts = options.parts();
+    let harness = options.harness();
+
+    let inputs: Vec<(&Entry, Result<String, String>)> = entries.into_iter()
+        .map(|entry| (entry, load(&input::resolve(entry.day, options.input.as_deref(), &dir), options.show_normalization)))
+        .collect();
+    let mut failed = false;
+    for day in pool::map(jobs, &inputs, |(entry, contents)| solve(entry, contents, &parts, &harness)) {
+        failed |= !emit(day);
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+// Solves the selected day from a reader over its input, for inputs too large
+// to load.
+fn run_streaming(options: &RunOptions, mut emit: impl FnMut(Vec<Report>) -> bool) -> ExitCode {
+    let entry = match select(options) {
+        Ok(entries) => entries[0],
+        Err(code)   => return code,
+    };
+    let Some(solver) = registry::find_streaming(entry.day) else {
+        eprintln!("error: day {} cannot solve from a stream", entry.day);
+        return ExitCode::from(2);
+    };
+
+    let source = input::resolve(entry.day, options.input.as_deref(), &input::input_dir(options.input_dir.as_deref()));
+    match emit(run::stream_day(entry.day, solver, &source, &options.parts(), &options.harness())) {
+        true  => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn run(options: &RunOptions, settings: &RunSettings) -> ExitCode {
+    // Text is printed day by day; the other formats are one document at the end.
+    let mut collected = Vec::new();
+    let mut emit = |reports: Vec<Report>| {
+        let ok = !reports.iter().any(Report::failed);
+        match settings.format {
+            Format::Text => run::print(&reports),
+            _            => {
+                run::print_diagnostics(&reports);
+                run::print_traces(&reports);
+                collected.extend(reports);
+            },
+        }
+        ok
+    };
+
+    let code = match settings.jobs {
+        _ if settings.stream => run_streaming(options, emit),
+        1                    => {
+            let (parts, harness) = (options.parts(), options.harness());
+            for_each_day(options, |entry, contents| emit(solve(entry, &contents, &parts, &harness)))
+        },
+        jobs                 => run_parallel(options, jobs, emit),
+    };
+
+    match settings.format {
+        Format::Text => (),
+        Format::Json => print!("{}", run::to_json(&collected)),
+        Format::Csv  => print!("{}", run::to_csv(&collected)),
+    }
+    code
+}
+
+fn bench(options: &RunOptions, settings: &BenchOptions) -> ExitCode {
+    let (parts, harness) = (options.parts(), options.harness());
+
+    for_each_day(options, |entry, contents| match contents {
+        Ok(contents) => bench::bench_day(entry, &contents, &parts, settings, &harness),
+        Err(message) => {
+            println!("Day {}: error: {}", entry.day, message);
+            false
+        },
+    })
+}
+
+fn verify(options: &RunOptions, settings: &VerifyOptions) -> ExitCode {
+    let path = settings.answers.as_ref().map(PathBuf::from)
+        .unwrap_or_else(|| input::input_dir(options.input_dir.as_deref()).join(ANSWERS_FILE));
+    let mut book = match AnswerBook::load(&path) {
+        Ok(book) => book,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        },
+    };
+    let known = book.clone();
+    let (parts, harness) = (options.parts(), options.harness());
+
+    let code = for_each_day(options, |entry, contents| match contents {
+        Ok(contents) => verify::verify_day(entry, &contents, &parts, &harness, &mut book, settings),
+        Err(message) => {
+            println!("Day {}: error: {}", entry.day, message);
+            false
+        },
+    });
+
+    if book != known {
+        if let Err(err) = book.save(&path) {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+        println!("Updated the answers in {}", path.display());
+    }
+    code
+}
+
+fn batch(options: &RunOptions, settings: &BatchOptions) -> ExitCode {
+    let entry = match select(options) {
+        Ok(entries) => entries[0],
+        Err(code)   => return code,
+    };
+    let book = match settings.check {
+        true  => {
+            let path = settings.answers.as_ref().map(PathBuf::from)
+                .unwrap_or_else(|| input::input_dir(options.input_dir.as_deref()).join(ANSWERS_FILE));
+            match AnswerBook::load(&path) {
+                Ok(book) => Some(book),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                },
+            }
+        },
+        false => None,
+    };
+    let files = match batch::input_files(Path::new(&settings.inputs)) {
+        Ok(files) if !files.is_empty() => files,
+        Ok(_)                          => {
+            eprintln!("error: no inputs in {}", settings.inputs);
+            return ExitCode::FAILURE;
+        },
+        Err(err)                       => {
+            eprintln!("error: failed to read {}: {}", settings.inputs, err);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    let inputs: Vec<(String, Result<String, String>)> = files.into_iter()
+        .map(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            (name, load(&InputSource::Path(path), options.show_normalization))
+        })
+        .collect();
+    let rows = batch::batch_day(entry, &inputs, &options.parts(), &options.harness(), book.as_ref());
+
+    print!("{}", batch::render(&rows, settings.check));
+    for row in &rows {
+        if row.report.diagnostic.is_some() || !row.report.trace.is_empty() {
+            eprintln!("{}:", row.input);
+        }
+        run::print_diagnostics(std::slice::from_ref(&row.report));
+        run::print_traces(std::slice::from_ref(&row.report));
+    }
+    let failed = rows.iter().filter(|row| row.failed()).count();
+    println!("Day {}: {} input(s), {} failed part(s)", entry.day, inputs.len(), failed);
+
+    if failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+// Runs every implementation of the selected parts on one input and reports
+// how they compare; fails if any disagree or fail.
+fn diff(options: &RunOptions) -> ExitCode {
+    let entry = match select(options) {
+        Ok(entries) => entries[0],
+        Err(code)   => return code,
+    };
+    let parts: Vec<Part> = options.parts().into_iter()
+        .filter(|&part| entry.solver.implementations(part).len() > 1)
+        .collect();
+    if parts.is_empty() {
+        eprintln!("error: day {} has only one implementation of each part", entry.day);
+        return ExitCode::from(2);
+    }
+
+    let source = input::resolve(entry.day, options.input.as_deref(), &input::input_dir(options.input_dir.as_deref()));
+    let comparisons = match load(&source, options.show_normalization).and_then(|contents| diff::diff_day(entry, &contents, &parts, &options.harness())) {
+        Ok(comparisons) => comparisons,
+        Err(message)    => {
+            println!("Day {}: error: {}", entry.day, message);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    print!("{}", diff::render(&comparisons));
+    let disagreements = comparisons.iter().filter(|comparison| comparison.disagrees()).count();
+    println!("Day {}: {} part(s) compared, {} disagreement(s)", entry.day, comparisons.len(), disagreements);
+
+    if disagreements > 0 || comparisons.iter().any(diff::Comparison::failed) { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+// Writes a synthetic input to --output or stdout, and its expected answers to
+// stderr so that they never end up in the input.
+fn generate(options: &RunOptions, settings: &GenerateOptions) -> ExitCode {
+    let day = options.day.unwrap_or_default();
+    let Some(generator) = registry::find_generator(day) else {
+        eprintln!("error: day {} has no generator", day);
+        return ExitCode::from(2);
+    };
+    let generated = generator(settings.seed, settings.size);
+
+    match &settings.output {
+        Some(path) => if let Err(err) = std::fs::write(path, &generated.input) {
+            eprintln!("error: failed to write {}: {}", path, err);
+            return ExitCode::FAILURE;
+        },
+        None       => print!("{}", generated.input),
+    }
+    for part in Part::ALL {
+        match generated.answer(part) {
+            Some(answer) => eprintln!("Day {}, Part {}: {}", day, part, answer),
+            None         => eprintln!("Day {}, Part {}: unknown", day, part),
+        }
+    }
+
+    if settings.record {
+        let path = input::input_dir(options.input_dir.as_deref()).join(ANSWERS_FILE);
+        let mut book = match AnswerBook::load(&path) {
+            Ok(book) => book,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            },
+        };
+        let fingerprint = fingerprint(&generated.input);
+        for part in Part::ALL {
+            if let Some(answer) = generated.answer(part) {
+                book.record(day, part, &fingerprint, answer);
+            }
+        }
+        if let Err(err) = book.save(&path) {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+        eprintln!("Recorded the answers in {}", path.display());
+    }
+    ExitCode::SUCCESS
+}
+
+// Fuzzes every selected day, saving the first input that makes one panic to
+// its corpus, where the tests keep running it.
+fn fuzz(options: &RunOptions, settings: &FuzzOptions) -> ExitCode {
+    let entries = match select(options) {
+        Ok(entries) => entries,
+        Err(code)   => return code,
+    };
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let Some(root) = scaffold::workspace_root(&cwd) else {
+        eprintln!("error: no Cargo workspace above {}", cwd.display());
+        return ExitCode::FAILURE;
+    };
+    let seed = settings.seed
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_nanos() as u64));
+    println!("Fuzzing with seed {}", seed);
+
+    let mut failed = false;
+    for entry in entries {
+        let corpus: Vec<Vec<u8>> = fuzz::corpus(&root, entry.day).into_iter().map(|(_, input)| input).collect();
+        let Some(crash) = fuzz::fuzz_day(entry, &corpus, settings.runs, seed) else {
+            println!("Day {}: {} input(s), no panics", entry.day, corpus.len() + settings.runs);
+            continue;
+        };
+        failed = true;
+
+        let dir = fuzz::corpus_dir(&root, entry.day);
+        let path = dir.join(format!("crash-{}", fingerprint(&String::from_utf8_lossy(&crash.input))));
+        println!("Day {}: panicked: {}", entry.day, crash.message);
+        match fs::create_dir_all(&dir).and_then(|()| fs::write(&path, &crash.input)) {
+            Ok(())   => println!("  saved the input to {}", path.display()),
+            Err(err) => println!("  failed to save the input to {}: {}", path.display(), err),
+        }
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn fetch(options: &RunOptions, settings: &SiteOptions) -> ExitCode {
+    let client = match site::client(settings) {
+        Ok(client)   => client,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return ExitCode::FAILURE;
+        },
+    };
+    let entries = match select(options) {
+        Ok(entries) => entries,
+        Err(code)   => return code,
+    };
+    let dir = input::input_dir(options.input_dir.as_deref());
+
+    let mut failed = false;
+
+    for entry in entries {
+        failed |= !site::fetch_day(&client, entry, &dir);
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn puzzle(options: &RunOptions, settings: &SiteOptions) -> ExitCode {
+    let result = site::client(settings)
+        .and_then(|client| client.puzzle(options.day.unwrap_or_default()).map_err(|err| err.to_string()));
+
+    match result {
+        Ok(text)     => {
+            println!("{}", text.trim_end());
+            ExitCode::SUCCESS
+        },
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn submit(options: &RunOptions, settings: &SiteOptions) -> ExitCode {
+    let client = match site::client(settings) {
+        Ok(client)   => client,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return ExitCode::FAILURE;
+        },
+    };
+    let entry = match select(options) {
+        Ok(entries) => entries[0],
+        Err(code)   => return code,
+    };
+
+    // Without an explicit --input, solve the cached input, fetching it if need be.
+    let dir = input::input_dir(options.input_dir.as_deref());
+    let contents = match &options.input {
+        Some(_) => load(&input::resolve(entry.day, options.input.as_deref(), &dir), options.show_normalization),
+        None    => client.cached_input(entry.day, &dir).map(|(contents, _)| input::normalize(&contents).0).map_err(|err| err.to_string()),
+    };
+    let contents = match contents {
+        Ok(contents) => contents,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    match site::submit_day(&client, entry, &contents, &options.parts(), &options.harness(), settings.answer.as_deref()) {
+        true  => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn mock(options: &MockOptions) -> ExitCode {
+    let dir = input::input_dir(options.input_dir.as_deref());
+    let path = options.answers.as_ref().map(PathBuf::from).unwrap_or_else(|| dir.join(ANSWERS_FILE));
+    let book = match AnswerBook::load(&path) {
+        Ok(book) => book,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        },
+    };
+    let puzzles = site::mock_puzzles(&dir, &book);
+    let (days, answers) = (puzzles.days(), puzzles.answers());
+
+    match MockServer::start(&format!("127.0.0.1:{}", options.port), puzzles) {
+        Ok(server) => {
+            println!("Serving {} input(s) and {} answer(s) from {} on {}", days, answers, dir.display(), server.url());
+            server.wait();
+            ExitCode::SUCCESS
+        },
+        Err(err)   => {
+            eprintln!("error: failed to listen on port {}: {}", options.port, err);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn new(day: u8) -> ExitCode {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let Some(root) = scaffold::workspace_root(&cwd) else {
+        eprintln!("error: no Cargo workspace above {}", cwd.display());
+        return ExitCode::FAILURE;
+    };
+
+    match scaffold::new_day(&root, day) {
+        Ok(created) => {
+            for path in created {
+                println!("created {}", path.display());
+            }
+            println!("registered day{} in the workspace and the runner", day);
+            ExitCode::SUCCESS
+        },
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+// Every registered day, with the names of any part's several implementations.
+fn list() -> ExitCode {
+    for entry in registry::DAYS {
+        let implementations: Vec<String> = Part::ALL.iter()
+            .map(|&part| (part, entry.solver.implementations(part)))
+            .filter(|(_, names)| !names.is_empty())
+            .map(|(part, names)| format!("part {}: {}", part, names.join(", ")))
+            .collect();
+        match implementations.is_empty() {
+            true  => println!("day{}", entry.day),
+            false => println!("day{:<4}{}", entry.day, implementations.join("; ")),
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match cli::parse(&args) {
+        Ok(Command::Run(options, settings))      => run(&options, &settings),
+        Ok(Command::Bench(options, settings))    => bench(&options, &settings),
+        Ok(Command::Verify(options, settings))   => verify(&options, &settings),
+        Ok(Command::Batch(options, settings))    => batch(&options, &settings),
+        Ok(Command::Diff(options))               => diff(&options),
+        Ok(Command::Generate(options, settings)) => generate(&options, &settings),
+        Ok(Command::Fuzz(options, settings))     => fuzz(&options, &settings),
+        Ok(Command::Fetch(options, settings))    => fetch(&options, &settings),
+        Ok(Command::Puzzle(options, settings))   => puzzle(&options, &settings),
+        Ok(Command::Submit(options, settings))   => submit(&options, &settings),
+        Ok(Command::Mock(options))               => mock(&options),
+        Ok(Command::New(day))                    => new(day),
+        Ok(Command::List)                        => list(),
+        Ok(Command::Help)                        => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        },
+        Err(message)                             => {
+            eprintln!("error: {}\n\n{}", message, cli::USAGE);
+            ExitCode::from(2)
+        },
+    }
+}
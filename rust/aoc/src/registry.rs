@@ -0,0 +1,53 @@
+use aoc_common::{generate::Generator, Solver, Streaming};
+
+pub struct Entry {
+    pub day: u8,
+    pub solver: &'static dyn Solver,
+}
+
+pub const DAYS: &[Entry] = &[
+    Entry { day: 1,  solver: &day1::Day1 },
+    Entry { day: 2,  solver: &day2::Day2 },
+    Entry { day: 3,  solver: &day3::Day3 },
+    Entry { day: 4,  solver: &day4::Day4 },
+    Entry { day: 5,  solver: &day5::Day5 },
+    Entry { day: 6,  solver: &day6::Day6 },
+    Entry { day: 7,  solver: &day7::Day7 },
+    Entry { day: 8,  solver: &day8::Day8 },
+    Entry { day: 9,  solver: &day9::Day9 },
+    Entry { day: 10, solver: &day10::Day10 },
+];
+
+pub fn find(day: u8) -> Option<&'static Entry> {
+    DAYS.iter().find(|entry| entry.day == day)
+}
+
+// Days that can also solve straight from a reader, for `run --stream`.
+pub const STREAMING: &[(u8, &dyn Streaming)] = &[
+    (1, &day1::Day1),
+    (2, &day2::Day2),
+    (4, &day4::Day4),
+    (9, &day9::Day9),
+];
+
+pub fn find_streaming(day: u8) -> Option<&'static dyn Streaming> {
+    STREAMING.iter().find(|&&(d, _)| d == day).map(|&(_, solver)| solver)
+}
+
+// Days that can generate synthetic inputs, for `generate`.
+pub const GENERATORS: &[(u8, Generator)] = &[
+    (1,  day1::generate::generate),
+    (2,  day2::generate::generate),
+    (3,  day3::generate::generate),
+    (4,  day4::generate::generate),
+    (5,  day5::generate::generate),
+    (6,  day6::generate::generate),
+    (7,  day7::generate::generate),
+    (8,  day8::generate::generate),
+    (9,  day9::generate::generate),
+    (10, day10::generate::generate),
+];
+
+pub fn find_generator(day: u8) -> Option<Generator> {
+    GENERATORS.iter().find(|&&(d, _)| d == day).map(|&(_, generator)| generator)
+}
@@ -0,0 +1,350 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
+
+use aoc_common::{
+    budget::Budget,
+    fuzz,
+    timing,
+    trace::{self, Level, Trace},
+    Answer, Error, InputSource, Part, Streaming,
+};
+
+use crate::registry::Entry;
+
+#[derive(Debug)]
+pub enum Outcome {
+    Solved(Answer),
+    NotImplemented,
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Outcome,
+    // Source snippet pointing at the input that failed to parse, if any.
+    pub diagnostic: Option<String>,
+    // Wall-clock time of the shared parse step and of this part alone.
+    pub parse: Option<Duration>,
+    pub solve: Option<Duration>,
+    // Events and counters the solver emitted while tracing, the parse step's
+    // included with the first part.
+    pub trace: Trace,
+}
+
+impl Report {
+    pub fn new(day: u8, part: Part, outcome: Outcome) -> Self {
+        Self { day, part, outcome, diagnostic: None, parse: None, solve: None, trace: Trace::default() }
+    }
+
+    fn from_error(day: u8, part: Part, err: &Error, contents: &str) -> Self {
+        let diagnostic = match err {
+            Error::Parse(err) => err.snippet(contents),
+            _                 => None,
+        };
+        Self { diagnostic, ..Self::new(day, part, Outcome::Failed(err.to_string())) }
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(self.outcome, Outcome::Failed(_))
+    }
+}
+
+// Runs `f`, catching a panic and returning its message instead, so that one
+// broken solver is reported as a failure rather than taking the others down.
+fn isolate<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| format!("panicked: {}", fuzz::panic_message(&*payload)))
+}
+
+// How each parse and each part is run: the budget it may spend and the level
+// of trace events to collect from it, if any.
+#[derive(Debug, Clone, Default)]
+pub struct Harness {
+    pub budget: Budget,
+    pub trace: Option<Level>,
+}
+
+impl Harness {
+    // Runs `f` under a fresh budget, isolated from panics, along with
+    // whatever it traced (nothing if it panicked).
+    pub fn attempt<T>(&self, f: impl FnOnce() -> T) -> (Result<T, String>, Trace) {
+        let run = || match self.trace {
+            Some(level) => trace::collect(level, || self.budget.run(f)),
+            None        => (self.budget.run(f), Trace::default()),
+        };
+        match isolate(run) {
+            Ok((value, trace)) => (Ok(value), trace),
+            Err(panic)         => (Err(panic), Trace::default()),
+        }
+    }
+}
+
+// Parses the input once and solves every requested part from it, each step
+// within the `harness`. A parse failure is reported against each part that
+// could not run, with the diagnostic attached to the first of them only.
+pub fn run_day(entry: &Entry, contents: &str, parts: &[Part], harness: &Harness) -> Vec<Report> {
+    let ((parsed, mut parse_trace), parse) = timing::time(|| harness.attempt(|| entry.solver.parse(contents)));
+
+    let mut reports: Vec<Report> = match parsed {
+        Ok(Ok(input)) => parts.iter()
+            .map(|&part| {
+                let ((result, trace), solve) = timing::time(|| harness.attempt(|| entry.solver.solve(part, input.as_ref())));
+                let report = match result {
+                    Ok(Ok(answer))                 => Report::new(entry.day, part, Outcome::Solved(answer)),
+                    Ok(Err(Error::NotImplemented)) => Report::new(entry.day, part, Outcome::NotImplemented),
+                    Ok(Err(err))                   => Report::from_error(entry.day, part, &err, contents),
+                    Err(panic)                     => Report::new(entry.day, part, Outcome::Failed(panic)),
+                };
+                Report { solve: Some(solve), trace, ..report }
+            })
+            .collect(),
+        Ok(Err(err))  => parts.iter().enumerate()
+            .map(|(i, &part)| {
+                let mut report = Report::from_error(entry.day, part, &err, contents);
+                if i > 0 {
+                    report.diagnostic = None;
+                }
+                report
+            })
+            .collect(),
+        Err(panic)    => parts.iter()
+            .map(|&part| Report::new(entry.day, part, Outcome::Failed(panic.clone())))
+            .collect(),
+    };
+
+    if let Some(first) = reports.first_mut() {
+        parse_trace.append(std::mem::take(&mut first.trace));
+        first.trace = parse_trace;
+    }
+    reports.into_iter().map(|report| Report { parse: Some(parse), ..report }).collect()
+}
+
+// Solves each part from a fresh reader over `source`, never holding the whole
+// input. There is no separate parse step to time.
+pub fn stream_day(day: u8, solver: &dyn Streaming, source: &InputSource, parts: &[Part], harness: &Harness) -> Vec<Report> {
+    parts.iter()
+        .map(|&part| {
+            let ((result, trace), solve) = timing::time(|| harness.attempt(|| solver.stream(part, &mut source.open()?, &source.to_string())));
+            let outcome = match result {
+                Ok(Ok(answer))                 => Outcome::Solved(answer),
+                Ok(Err(Error::NotImplemented)) => Outcome::NotImplemented,
+                Ok(Err(err))                   => Outcome::Failed(err.to_string()),
+                Err(panic)                     => Outcome::Failed(panic),
+            };
+            Report { solve: Some(solve), trace, ..Report::new(day, part, outcome) }
+        })
+        .collect()
+}
+
+fn timings(report: &Report, with_parse: bool) -> String {
+    let mut timings: Vec<String> = Vec::new();
+    if let (true, Some(parse)) = (with_parse, report.parse) {
+        timings.push(format!("parse {}", timing::format_duration(parse)));
+    }
+    if let Some(solve) = report.solve {
+        timings.push(format!("solve {}", timing::format_duration(solve)));
+    }
+    if timings.is_empty() { String::new() } else { format!("  [{}]", timings.join(", ")) }
+}
+
+// Prints one day's reports; the shared parse time is shown on the first line.
+pub fn print(reports: &[Report]) {
+    for (i, report) in reports.iter().enumerate() {
+        let text = format!("Day {}, Part {}:", report.day, report.part);
+        let timings = timings(report, i == 0);
+        match &report.outcome {
+            Outcome::Solved(answer)  => println!("{} {}{}", text, answer, timings),
+            Outcome::NotImplemented  => println!("{} Not Done Yet!{}", text, timings),
+            Outcome::Failed(message) => println!("{} error: {}{}", text, message, timings),
+        }
+    }
+    print_diagnostics(reports);
+    print_traces(reports);
+}
+
+pub fn print_diagnostics(reports: &[Report]) {
+    for diagnostic in reports.iter().filter_map(|report| report.diagnostic.as_ref()) {
+        eprintln!("{}\n", diagnostic);
+    }
+}
+
+// Traces go to stderr in every format, so they never mix with the results.
+pub fn print_traces(reports: &[Report]) {
+    for report in reports.iter().filter(|report| !report.trace.is_empty()) {
+        eprint!("Day {}, Part {} trace:\n{}", report.day, report.part, report.trace);
+    }
+}
+
+// The fields of the machine-readable formats, in schema order. Every report
+// carries its day's shared parse time; timings are whole nanoseconds and
+// absent when the step never ran.
+struct Row {
+    day: u8,
+    part: Part,
+    status: &'static str,
+    answer: Option<String>,
+    error: Option<String>,
+    parse_ns: Option<u128>,
+    solve_ns: Option<u128>,
+}
+
+impl From<&Report> for Row {
+    fn from(report: &Report) -> Self {
+        let (status, answer, error) = match &report.outcome {
+            Outcome::Solved(answer)  => ("solved", Some(answer.to_string()), None),
+            Outcome::NotImplemented  => ("not_implemented", None, None),
+            Outcome::Failed(message) => ("error", None, Some(message.clone())),
+        };
+        Self {
+            day: report.day,
+            part: report.part,
+            status,
+            answer,
+            error,
+            parse_ns: report.parse.map(|d| d.as_nanos()),
+            solve_ns: report.solve.map(|d| d.as_nanos()),
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\'   => {
+                out.push('\\');
+                out.push(c);
+            },
+            '\n'         => out += "\\n",
+            '\r'         => out += "\\r",
+            '\t'         => out += "\\t",
+            c if c < ' ' => out += &format!("\\u{:04x}", c as u32),
+            c            => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_or_null<T>(value: Option<T>, f: impl Fn(T) -> String) -> String {
+    value.map_or_else(|| "null".to_string(), f)
+}
+
+// A JSON array with one object per report:
+// `{"day", "part", "status", "answer", "error", "parse_ns", "solve_ns"}`.
+pub fn to_json(reports: &[Report]) -> String {
+    let rows: Vec<String> = reports.iter().map(Row::from).map(|row| format!(
+        "  {{\"day\": {}, \"part\": {}, \"status\": \"{}\", \"answer\": {}, \"error\": {}, \"parse_ns\": {}, \"solve_ns\": {}}}",
+        row.day,
+        row.part,
+        row.status,
+        json_or_null(row.answer.as_deref(), json_string),
+        json_or_null(row.error.as_deref(), json_string),
+        json_or_null(row.parse_ns, |ns| ns.to_string()),
+        json_or_null(row.solve_ns, |ns| ns.to_string()),
+    )).collect();
+
+    if rows.is_empty() { "[]\n".to_string() } else { format!("[\n{}\n]\n", rows.join(",\n")) }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// A header line and one record per report, empty fields standing for nulls.
+pub fn to_csv(reports: &[Report]) -> String {
+    let mut out = String::from("day,part,status,answer,error,parse_ns,solve_ns\n");
+    for row in reports.iter().map(Row::from) {
+        out += &format!(
+            "{},{},{},{},{},{},{}\n",
+            row.day,
+            row.part,
+            row.status,
+            csv_field(row.answer.as_deref().unwrap_or("")),
+            csv_field(row.error.as_deref().unwrap_or("")),
+            row.parse_ns.map(|ns| ns.to_string()).unwrap_or_default(),
+            row.solve_ns.map(|ns| ns.to_string()).unwrap_or_default(),
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{Answer, Solution};
+
+    struct Panics;
+
+    impl Solution for Panics {
+        type Input = ();
+
+        fn parse(&self, _contents: &str) -> aoc_common::Result<()> {
+            aoc_common::event!(Level::Info, "panics.parse");
+            Ok(())
+        }
+
+        fn part_1(&self, _input: &()) -> aoc_common::Result<Answer> {
+            panic!("index out of bounds")
+        }
+
+        fn part_2(&self, _input: &()) -> aoc_common::Result<Answer> {
+            trace::count("panics.survived", 1);
+            Ok(2.into())
+        }
+    }
+
+    #[test]
+    fn panics_are_isolated_to_their_part() {
+        let reports = run_day(&Entry { day: 99, solver: &Panics }, "", &Part::ALL, &Harness::default());
+
+        assert!(matches!(&reports[0].outcome, Outcome::Failed(message) if message == "panicked: index out of bounds"));
+        assert!(matches!(&reports[1].outcome, Outcome::Solved(answer) if *answer == 2.into()));
+        assert!(reports.iter().all(|report| report.trace.is_empty()));
+    }
+
+    #[test]
+    fn traces_follow_their_part() {
+        let harness = Harness { trace: Some(Level::Info), ..Harness::default() };
+        let reports = run_day(&Entry { day: 99, solver: &Panics }, "", &Part::ALL, &harness);
+
+        assert_eq!(reports[0].trace.to_string(), "  [info] panics.parse\n");
+        assert_eq!(reports[1].trace.to_string(), "  panics.survived = 1\n");
+    }
+
+    fn reports() -> Vec<Report> {
+        vec![
+            Report { parse: Some(Duration::from_micros(3)), solve: Some(Duration::from_nanos(250)), ..Report::new(7, Part::One, Outcome::Solved(6440.into())) },
+            Report::new(7, Part::Two, Outcome::NotImplemented),
+            Report::new(8, Part::One, Outcome::Failed("failed to read \"day08.txt\", sorry".to_string())),
+        ]
+    }
+
+    #[test]
+    fn renders_json() {
+        assert_eq!(to_json(&reports()), "[
+  {\"day\": 7, \"part\": 1, \"status\": \"solved\", \"answer\": \"6440\", \"error\": null, \"parse_ns\": 3000, \"solve_ns\": 250},
+  {\"day\": 7, \"part\": 2, \"status\": \"not_implemented\", \"answer\": null, \"error\": null, \"parse_ns\": null, \"solve_ns\": null},
+  {\"day\": 8, \"part\": 1, \"status\": \"error\", \"answer\": null, \"error\": \"failed to read \\\"day08.txt\\\", sorry\", \"parse_ns\": null, \"solve_ns\": null}
+]
+");
+        assert_eq!(to_json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn renders_csv() {
+        assert_eq!(to_csv(&reports()), "\
+day,part,status,answer,error,parse_ns,solve_ns
+7,1,solved,6440,,3000,250
+7,2,not_implemented,,,,
+8,1,error,,\"failed to read \"\"day08.txt\"\", sorry\",,
+");
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn day_lib(day: u8) -> String {
+    format!(r#"use aoc_common::{{Result, Solution, Source}};
+
+pub use aoc_common::read_file;
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input = Vec<String>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {{
+        let source = Source::new(contents);
+
+        Ok(source.lines().map(|line| line.to_string()).collect())
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+    use aoc_common::fixture;
+    
+    #[test]
+    fn part_1_works() {{
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day{day}.parse(&input).unwrap();
+
+        let result = Day{day}.part_1(&input).unwrap();
+        assert_eq!(result, 0.into());
+    }}
+
+    #[test]
+    fn part_2_works() {{
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day{day}.parse(&input).unwrap();
+
+        let result = Day{day}.part_2(&input).unwrap();
+        assert_eq!(result, 0.into());
+    }}
+}}
+"#)
+}
+
+fn day_manifest(day: u8) -> String {
+    format!(r#"[package]
+name = "day{day}"
+version.workspace = true
+edition.workspace = true
+
+[dependencies]
+aoc-common.workspace = true
+"#)
+}
+
+// Inserts `line` among the lines for which `day_of` yields a day number,
+// keeping them in day order. Fails if the day is already listed or no such
+// lines exist to anchor the insertion.
+fn insert_day(text: &str, day: u8, line: &str, day_of: impl Fn(&str) -> Option<u8>) -> Result<String, String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let days: Vec<(usize, u8)> = lines.iter().enumerate()
+        .filter_map(|(i, line)| day_of(line).map(|d| (i, d)))
+        .collect();
+
+    if days.iter().any(|&(_, d)| d == day) {
+        return Err(format!("day {} is already registered", day));
+    }
+    let at = match days.iter().find(|&&(_, d)| d > day) {
+        Some(&(i, _)) => i,
+        None          => days.last().map(|&(i, _)| i + 1).ok_or("no existing days to anchor on")?,
+    };
+
+    lines.insert(at, line);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn leading_day(text: &str) -> Option<u8> {
+    let digits: String = text.strip_prefix("day")?.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+fn member_day(line: &str) -> Option<u8> {
+    leading_day(line.trim().strip_prefix('"')?.strip_suffix("\",")?)
+}
+
+fn dependency_day(line: &str) -> Option<u8> {
+    let (name, _) = line.split_once([' ', '.'])?;
+    leading_day(name).filter(|day| name == format!("day{}", day))
+}
+
+fn registry_day(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix("Entry { day:")?;
+    rest.split(',').next()?.trim().parse().ok()
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path.display(), err))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("failed to write {}: {}", path.display(), err))
+}
+
+// Walks up from `start` to the directory whose Cargo.toml declares the workspace.
+pub fn workspace_root(start: &Path) -> Option<PathBuf> {
+    start.ancestors()
+        .find(|dir| fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|text| text.contains("[workspace]")))
+        .map(Path::to_path_buf)
+}
+
+// Creates `dayN/` from the template and registers it with the workspace
+// manifest, the runner's dependencies and the solver registry.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {} is outside 1..=25", day));
+    }
+    let dir = root.join(format!("day{}", day));
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+
+    let workspace = root.join("Cargo.toml");
+    let runner = root.join("aoc").join("Cargo.toml");
+    let registry = root.join("aoc").join("src").join("registry.rs");
+
+    // Work out every registration before touching the disk, so that a layout
+    // we do not understand leaves nothing half-done behind.
+    let located = |path: &Path, text: Result<String, String>| text.map_err(|err| format!("{}: {}", path.display(), err));
+    let workspace_text = located(&workspace, insert_day(&read(&workspace)?, day, &format!("    \"day{}\",", day), member_day)
+        .and_then(|text| insert_day(&text, day, &format!("day{day} = {{ path = \"day{day}\" }}"), dependency_day)))?;
+    let runner_text = located(&runner, insert_day(&read(&runner)?, day, &format!("day{}.workspace = true", day), dependency_day))?;
+    let registry_text = located(&registry, insert_day(&read(&registry)?, day,
+        &format!("    Entry {{ day: {:<4}solver: &day{day}::Day{day} }},", format!("{},", day)), registry_day))?;
+
+    fs::create_dir_all(dir.join("src")).map_err(|err| format!("failed to create {}: {}", dir.display(), err))?;
+    let created = vec![
+        dir.join("Cargo.toml"),
+        dir.join("src").join("lib.rs"),
+        dir.join("src").join("test.dat"),
+    ];
+    write(&created[0], &day_manifest(day))?;
+    write(&created[1], &day_lib(day))?;
+    write(&created[2], "")?;
+
+    write(&workspace, &workspace_text)?;
+    write(&runner, &runner_text)?;
+    write(&registry, &registry_text)?;
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_in_day_order() {
+        let members = "members = [\n    \"aoc\",\n    \"day1\",\n    \"day3\",\n]\n";
+        assert_eq!(insert_day(members, 2, "    \"day2\",", member_day).unwrap(),
+                   "members = [\n    \"aoc\",\n    \"day1\",\n    \"day2\",\n    \"day3\",\n]\n");
+        assert_eq!(insert_day(members, 11, "    \"day11\",", member_day).unwrap(),
+                   "members = [\n    \"aoc\",\n    \"day1\",\n    \"day3\",\n    \"day11\",\n]\n");
+        assert!(insert_day(members, 3, "    \"day3\",", member_day).is_err());
+    }
+
+    #[test]
+    fn recognises_registered_days() {
+        assert_eq!(dependency_day("day10 = { path = \"day10\" }"), Some(10));
+        assert_eq!(dependency_day("day7.workspace = true"), Some(7));
+        assert_eq!(dependency_day("aoc-common.workspace = true"), None);
+        assert_eq!(registry_day("    Entry { day: 9,  solver: &day9::Day9 },"), Some(9));
+    }
+
+    #[test]
+    fn scaffolds_into_workspace() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("aoc/src")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\n    \"aoc\",\n    \"day1\",\n]\n\n[workspace.dependencies]\nday1 = { path = \"day1\" }\n").unwrap();
+        fs::write(root.join("aoc/Cargo.toml"), "[dependencies]\nday1.workspace = true\n").unwrap();
+        fs::write(root.join("aoc/src/registry.rs"), "pub const DAYS: &[Entry] = &[\n    Entry { day: 1,  solver: &day1::Day1 },\n];\n").unwrap();
+
+        let created = new_day(&root, 11).unwrap();
+        assert_eq!(created.len(), 3);
+        assert!(fs::read_to_string(root.join("day11/src/lib.rs")).unwrap().contains("pub struct Day11;"));
+        assert!(fs::read_to_string(root.join("Cargo.toml")).unwrap().contains("    \"day11\",\n]"));
+        assert!(fs::read_to_string(root.join("Cargo.toml")).unwrap().contains("day11 = { path = \"day11\" }"));
+        assert!(fs::read_to_string(root.join("aoc/Cargo.toml")).unwrap().contains("day11.workspace = true"));
+        assert!(fs::read_to_string(root.join("aoc/src/registry.rs")).unwrap()
+            .contains("    Entry { day: 11, solver: &day11::Day11 },\n];"));
+        assert_eq!(workspace_root(&root.join("day11/src")), Some(root.clone()));
+
+        assert!(new_day(&root, 11).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
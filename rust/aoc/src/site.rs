@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use aoc_client::{mock::Puzzles, Client, Config, Submission};
+use aoc_common::{
+    answers::{fingerprint, AnswerBook},
+    input, Part,
+};
+
+use crate::{
+    cli::SiteOptions,
+    registry::{self, Entry},
+    run::{self, Harness, Outcome},
+};
+
+// A client for the configured site, with --base-url taking precedence.
+pub fn client(settings: &SiteOptions) -> Result<Client, String> {
+    let mut config = Config::load().map_err(|err| err.to_string())?;
+    if let Some(base_url) = &settings.base_url {
+        config.base_url = Some(base_url.clone());
+    }
+    Ok(Client::from_config(&config))
+}
+
+pub fn fetch_day(client: &Client, entry: &Entry, dir: &Path) -> bool {
+    let path = input::input_path(dir, entry.day);
+    match client.cached_input(entry.day, dir) {
+        Ok((_, true))  => println!("Day {}: fetched {}", entry.day, path.display()),
+        Ok((_, false)) => println!("Day {}: cached {}", entry.day, path.display()),
+        Err(err)       => {
+            println!("Day {}: error: {}", entry.day, err);
+            return false;
+        },
+    }
+    true
+}
+
+// Submits `answer` for the single requested part, or else whatever the solver
+// computes for each part. Returns false unless every part ends up solved.
+pub fn submit_day(client: &Client, entry: &Entry, contents: &str, parts: &[Part], harness: &Harness, answer: Option<&str>) -> bool {
+    let answers: Vec<(Part, Option<String>)> = match answer {
+        Some(answer) => parts.iter().map(|&part| (part, Some(answer.to_string()))).collect(),
+        None         => run::run_day(entry, contents, parts, harness).into_iter()
+            .map(|report| match report.outcome {
+                Outcome::Solved(answer)  => (report.part, Some(answer.to_string())),
+                Outcome::NotImplemented  => {
+                    println!("Day {}, Part {}: Not Done Yet!", entry.day, report.part);
+                    (report.part, None)
+                },
+                Outcome::Failed(message) => {
+                    println!("Day {}, Part {}: error: {}", entry.day, report.part, message);
+                    (report.part, None)
+                },
+            })
+            .collect(),
+    };
+
+    let mut ok = true;
+    for (part, answer) in answers {
+        let Some(answer) = answer else {
+            ok = false;
+            continue;
+        };
+        let text = format!("Day {}, Part {}: {}", entry.day, part, answer);
+        match client.submit(entry.day, part, &answer) {
+            Ok(outcome @ (Submission::Correct | Submission::AlreadySolved)) => println!("{} -> {}", text, outcome),
+            Ok(outcome)                                                     => {
+                println!("{} -> {}", text, outcome);
+                ok = false;
+            },
+            Err(err)                                                        => {
+                println!("{} -> error: {}", text, err);
+                ok = false;
+            },
+        }
+    }
+    ok
+}
+
+// What the mock server serves: every registered day's input found in `dir`,
+// and whatever answers the book knows for those exact inputs.
+pub fn mock_puzzles(dir: &Path, book: &AnswerBook) -> Puzzles {
+    let mut puzzles = Puzzles::default();
+
+    for entry in registry::DAYS {
+        let Ok(contents) = aoc_common::read_file(input::input_path(dir, entry.day)) else { continue };
+        let fingerprint = fingerprint(&contents);
+        for part in Part::ALL {
+            if let Some(answer) = book.get(entry.day, part, &fingerprint) {
+                puzzles = puzzles.answer(entry.day, part, answer);
+            }
+        }
+        puzzles = puzzles.input(entry.day, contents);
+    }
+    puzzles
+}
@@ -0,0 +1,47 @@
+use aoc_common::{
+    answers::{fingerprint, AnswerBook, Verdict},
+    Part,
+};
+
+use crate::{
+    cli::VerifyOptions,
+    registry::Entry,
+    run::{self, Harness, Outcome},
+};
+
+// Solves the day and compares every answer with the book, recording unknown
+// answers when `record` is set and replacing mismatched ones when `overwrite`
+// is. Returns false on a failure or a mismatch left in place.
+pub fn verify_day(entry: &Entry, contents: &str, parts: &[Part], harness: &Harness, book: &mut AnswerBook, options: &VerifyOptions) -> bool {
+    let fingerprint = fingerprint(contents);
+    let mut ok = true;
+
+    for report in run::run_day(entry, contents, parts, harness) {
+        let text = format!("Day {}, Part {}:", report.day, report.part);
+        match &report.outcome {
+            Outcome::Solved(answer)  => match book.check(report.day, report.part, &fingerprint, answer) {
+                Verdict::Match                                      => println!("{} ok ({})", text, answer),
+                Verdict::Mismatch { expected } if options.overwrite => {
+                    book.record(report.day, report.part, &fingerprint, answer);
+                    println!("{} overwrote {} with {}", text, expected, answer);
+                },
+                Verdict::Mismatch { expected }                      => {
+                    println!("{} MISMATCH: got {}, expected {}", text, answer, expected);
+                    ok = false;
+                },
+                Verdict::Unknown if options.record                  => {
+                    book.record(report.day, report.part, &fingerprint, answer);
+                    println!("{} recorded ({})", text, answer);
+                },
+                Verdict::Unknown                                    => println!("{} unknown ({})", text, answer),
+            },
+            Outcome::NotImplemented  => println!("{} Not Done Yet!", text),
+            Outcome::Failed(message) => {
+                println!("{} error: {}", text, message);
+                ok = false;
+            },
+        }
+    }
+
+    ok
+}
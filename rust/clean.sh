@@ -1,9 +0,0 @@
-#!/bin/fish
-
-for dir in */
-	pushd $dir
-	cargo clean
-	popd
-end
-
-echo "done"
@@ -0,0 +1,83 @@
+use aoc_common::generate::{Generated, Rng};
+
+const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+// Spelled digits sharing a letter, with the first and last digit each spells.
+const OVERLAPS: [(&str, usize, usize); 8] = [
+    ("eightwo", 8, 2), ("twone", 2, 1), ("oneight", 1, 8), ("threeight", 3, 8),
+    ("fiveight", 5, 8), ("nineight", 9, 8), ("sevenine", 7, 9), ("eighthree", 8, 3),
+];
+
+// Letters in no digit's name, so filler never spells one by accident.
+const FILLER: &[u8] = b"abcdjklmpqyz";
+
+// A piece of a line: its text, the digit it starts and ends with when words
+// count, and its written digit, if it is one.
+struct Token {
+    text: String,
+    first: usize,
+    last: usize,
+    written: Option<usize>,
+}
+
+fn token(rng: &mut Rng) -> Token {
+    match rng.range(0..3) {
+        0 => {
+            let digit = rng.range(1..10);
+            Token { text: digit.to_string(), first: digit, last: digit, written: Some(digit) }
+        },
+        1 => {
+            let digit = rng.range(1..10);
+            Token { text: WORDS[digit - 1].to_string(), first: digit, last: digit, written: None }
+        },
+        _ => {
+            let &(text, first, last) = rng.pick(&OVERLAPS);
+            Token { text: text.to_string(), first, last, written: None }
+        },
+    }
+}
+
+// `size` calibration lines, each with at least one written digit and filler
+// between every pair of tokens.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let (mut input, mut part_1, mut part_2) = (String::new(), 0, 0);
+
+    for _ in 0..size {
+        let mut tokens: Vec<Token> = (0..rng.range(1..6)).map(|_| token(&mut rng)).collect();
+        if tokens.iter().all(|token| token.written.is_none()) {
+            let digit = rng.range(1..10);
+            let at = rng.range(0..tokens.len() + 1);
+            tokens.insert(at, Token { text: digit.to_string(), first: digit, last: digit, written: Some(digit) });
+        }
+
+        let written: Vec<usize> = tokens.iter().filter_map(|token| token.written).collect();
+        part_1 += written[0] * 10 + written[written.len() - 1];
+        part_2 += tokens[0].first * 10 + tokens[tokens.len() - 1].last;
+
+        for (i, token) in tokens.iter().enumerate() {
+            let filler = rng.range(usize::from(i > 0)..4);
+            input.extend((0..filler).map(|_| *rng.pick(FILLER) as char));
+            input += &token.text;
+        }
+        input.extend((0..rng.range(0..4)).map(|_| *rng.pick(FILLER) as char));
+        input.push('\n');
+    }
+
+    Generated::new(input, part_1, part_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day1;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day1, &generate(seed, 200));
+        }
+    }
+}
@@ -1,24 +1,13 @@
-use std::{error::Error, collections::{HashMap, VecDeque}};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::BufRead,
+};
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
-}
-
-pub fn part_1(contents: &str) -> u32 {
-    let result = contents.lines().map(|line| {
-            let first_digit = line.chars().filter(|c| c.is_digit(10)).next()
-                .unwrap().to_digit(10).unwrap();
-            let last_digit  = line.chars().filter(|c| c.is_digit(10)).last()
-                .unwrap().to_digit(10).unwrap();
+use aoc_common::{stream, Answer, Error, Implementation, ParseError, Part, Result, Solution, Streaming};
 
-            first_digit * 10 + last_digit
-            }
-        ).sum::<u32>();
-    result
-}
+pub mod generate;
 
-// Solve using Aho-Corasick algorithm
+pub use aoc_common::read_file;
 
 type NodeID = usize;
 type WordID = usize;
@@ -34,6 +23,12 @@ pub struct AhoCorasick {
     words: Vec<String>
 }
 
+impl Default for AhoCorasick {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AhoCorasick {
     pub fn new() -> Self {
         Self {
@@ -57,10 +52,6 @@ impl AhoCorasick {
         &self.nodes[id]
     }
 
-    fn get_words(&self) -> &Vec<String> {
-        &self.words
-    }
-
     fn get_edges(&self, id: NodeID) -> Vec<(char, NodeID)> {
         self.nodes[id].children.iter()
             .map(|(&c, &q)| (c, q))
@@ -149,62 +140,166 @@ impl AhoCorasick {
     }
 }
 
-pub fn part_2(contents: &str) -> usize {
+const PATTERNS: [&str; 18] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+                              "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+fn no_digit(number: usize, line: &str) -> Error {
+    Error::Parse(ParseError::new(number, 1, line, "a line containing a digit"))
+}
+
+fn calibration(number: usize, line: &str) -> Result<usize> {
+    let first_digit = line.chars().find_map(|c| c.to_digit(10))
+        .ok_or_else(|| no_digit(number, line))?;
+    let last_digit  = line.chars().rev().find_map(|c| c.to_digit(10))
+        .ok_or_else(|| no_digit(number, line))?;
+
+    Ok((first_digit * 10 + last_digit) as usize)
+}
+
+fn digit_matcher() -> AhoCorasick {
     let mut pma: AhoCorasick = AhoCorasick::new();
-    let mut map: HashMap<String, usize> = HashMap::new();
-    
-    let patterns = vec!["one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-                        "1", "2", "3", "4", "5", "6", "7", "8", "9"];
-    for (i, key) in patterns[..=8].iter().enumerate() {
-        map.insert(key.to_string(), i+1);
-    }
-    for (i, key) in patterns[9..].iter().enumerate() {
-        map.insert(key.to_string(), i+1);
+    pma.build(PATTERNS.to_vec());
+    pma
+}
+
+// Like `calibration`, but digits may also be spelled out; found with the
+// Aho-Corasick automaton from `digit_matcher`.
+fn spelled_calibration(pma: &AhoCorasick, number: usize, line: &str) -> Result<usize> {
+    let result = pma.find(line);
+    let matches = result.iter().enumerate()
+        .flat_map(|(index, inner_vec)| inner_vec.iter().map(move |&value| (index, value)));
+
+    let min_index = matches.clone().min_by_key(|&(_, value)| value).map(|(index, _)| index)
+        .ok_or_else(|| no_digit(number, line))?;
+    let max_index = matches.max_by_key(|&(_, value)| value).map(|(index, _)| index)
+        .ok_or_else(|| no_digit(number, line))?;
+
+    // Spelled and written digits alternate every nine patterns.
+    Ok((min_index % 9 + 1) * 10 + max_index % 9 + 1)
+}
+
+// The digit, written or spelled out, that `text` starts with.
+fn digit_at(text: &str) -> Option<usize> {
+    PATTERNS.iter().position(|pattern| text.starts_with(pattern)).map(|index| index % 9 + 1)
+}
+
+// Like `spelled_calibration`, but tries every pattern at every position,
+// from either end of the line, instead of building an automaton.
+fn naive_calibration(number: usize, line: &str) -> Result<usize> {
+    let first = line.char_indices().find_map(|(i, _)| digit_at(&line[i..]))
+        .ok_or_else(|| no_digit(number, line))?;
+    let last  = line.char_indices().rev().find_map(|(i, _)| digit_at(&line[i..]))
+        .ok_or_else(|| no_digit(number, line))?;
+
+    Ok(first * 10 + last)
+}
+
+fn naive_part_2(input: &[String]) -> Result<Answer> {
+    let result = input.iter().enumerate()
+        .map(|(i, line)| naive_calibration(i + 1, line))
+        .sum::<Result<usize>>()?;
+    Ok(result.into())
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<String>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        stream::lines(contents.as_bytes(), stream::IN_MEMORY).map(|line| line.map(|(_, text)| text)).collect()
     }
 
-    pma.build(patterns.clone());
+    fn part_1(&self, input: &Self::Input) -> Result<Answer> {
+        let result = input.iter().enumerate()
+            .map(|(i, line)| calibration(i + 1, line))
+            .sum::<Result<usize>>()?;
+        Ok(result.into())
+    }
 
-    let result: usize = contents.lines().map(|line| {
-        let result = pma.find(line);
-        
-        let min_index: usize = result.iter().enumerate()
-            .flat_map(|(index, inner_vec)|{
-                inner_vec.iter().map(move |&value| (index, value))
-            }).collect::<Vec<(usize, usize)>>()
-            .iter().min_by_key(|&&(_, value)| value).map(|&(index, _)| index).unwrap();
-        
-        let max_index: usize = result.iter().enumerate()
-            .flat_map(|(index, inner_vec)|{
-                inner_vec.iter().map(move |&value| (index, value))
-            }).collect::<Vec<(usize, usize)>>()
-            .iter().max_by_key(|&&(_, value)| value).map(|&(index, _)| index).unwrap();
+    fn part_2(&self, input: &Self::Input) -> Result<Answer> {
+        let pma = digit_matcher();
+        let result = input.iter().enumerate()
+            .map(|(i, line)| spelled_calibration(&pma, i + 1, line))
+            .sum::<Result<usize>>()?;
+        Ok(result.into())
+    }
 
-        let first_digit = map.get(patterns[min_index]).unwrap();
-        let last_digit = map.get(patterns[max_index]).unwrap();
+    fn implementations(&self) -> Vec<Implementation<Self::Input>> {
+        vec![
+            Implementation { name: "aho-corasick", part: Part::Two, solve: |input| Day1.part_2(input) },
+            Implementation { name: "naive",        part: Part::Two, solve: |input| naive_part_2(input) },
+        ]
+    }
+}
 
-        first_digit * 10 + last_digit
-    }).sum();
+impl Streaming for Day1 {
+    fn stream_part_1(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        let result = stream::lines(reader, path)
+            .map(|line| line.and_then(|(number, text)| calibration(number, &text)))
+            .sum::<Result<usize>>()?;
+        Ok(result.into())
+    }
 
-    result
+    fn stream_part_2(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        let pma = digit_matcher();
+        let result = stream::lines(reader, path)
+            .map(|line| line.and_then(|(number, text)| spelled_calibration(&pma, number, &text)))
+            .sum::<Result<usize>>()?;
+        Ok(result.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::fixture;
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test-1.dat").unwrap();
+        let input: String = read_file(fixture!("test-1.dat")).unwrap();
+        let input = Day1.parse(&input).unwrap();
 
-        let result = part_1(&input);
-        assert!(result == 142);
+        let result = Day1.part_1(&input).unwrap();
+        assert_eq!(result, 142.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test-2.dat").unwrap();
+        let input: String = read_file(fixture!("test-2.dat")).unwrap();
+        let input = Day1.parse(&input).unwrap();
 
-        let result = part_2(&input);
-        assert!(result == 281);
+        let result = Day1.part_2(&input).unwrap();
+        assert_eq!(result, 281.into());
+    }
+
+    #[test]
+    fn streaming_matches_parsed() {
+        let part_1: String = read_file(fixture!("test-1.dat")).unwrap();
+        let part_2: String = read_file(fixture!("test-2.dat")).unwrap();
+
+        assert_eq!(Day1.stream_part_1(&mut part_1.as_bytes(), stream::IN_MEMORY).unwrap(), 142.into());
+        assert_eq!(Day1.stream_part_2(&mut part_2.as_bytes(), stream::IN_MEMORY).unwrap(), 281.into());
+    }
+
+    #[test]
+    fn implementations_agree() {
+        let input: String = read_file(fixture!("test-2.dat")).unwrap();
+        let input = Day1.parse(&input).unwrap();
+
+        for implementation in Day1.implementations() {
+            assert_eq!((implementation.solve)(&input).unwrap(), 281.into(), "{}", implementation.name);
+        }
+        assert_eq!(naive_calibration(1, "eighthree").unwrap(), 83);
+    }
+
+    #[test]
+    fn line_without_digits_is_reported() {
+        let input = Day1.parse("1abc2\nabc\n").unwrap();
+
+        match Day1.part_1(&input) {
+            Err(Error::Parse(err)) => assert_eq!((err.line, err.found.as_str()), (2, "abc")),
+            other                  => panic!("unexpected {:?}", other),
+        }
     }
 }
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use aoc_common::{generate::{Generated, Rng}, grid::AROUND};
+
+// Whether adding `(y, x)` keeps `blob` one piece without holes, and without
+// cells meeting only at a corner: the blob cells around it must form a
+// single run, and a diagonal one needs a side neighbour of the run with it.
+fn can_grow(blob: &[Vec<bool>], (y, x): (usize, usize)) -> bool {
+    let inside: Vec<bool> = AROUND.iter()
+        .map(|&(dy, dx)| blob[(y as isize + dy) as usize][(x as isize + dx) as usize])
+        .collect();
+    let touches = (0..8).step_by(2).any(|i| inside[i]);
+    let pinched = (1..8).step_by(2).any(|i| inside[i] && !inside[i - 1] && !inside[(i + 1) % 8]);
+    let runs = (0..8).filter(|&i| inside[i] && !inside[(i + 7) % 8]).count();
+    touches && !pinched && runs == 1
+}
+
+// A random blob of up to `cells` cells on a grid `side` cells wide, never
+// touching the grid's edge.
+fn blob(rng: &mut Rng, cells: usize, side: usize) -> Vec<Vec<bool>> {
+    let mut blob = vec![vec![false; side]; side];
+    blob[side / 2][side / 2] = true;
+    let mut frontier = vec![(side / 2, side / 2)];
+    let (mut grown, mut failures) = (1, 0);
+
+    while grown < cells && failures < 100 * frontier.len() {
+        let (y, x) = *rng.pick(&frontier);
+        let (dy, dx) = AROUND[2 * rng.range(0..4)];
+        let next = ((y as isize + dy) as usize, (x as isize + dx) as usize);
+        if next.0 == 0 || next.1 == 0 || next.0 == side - 1 || next.1 == side - 1 || blob[next.0][next.1] || !can_grow(&blob, next) {
+            failures += 1;
+            continue;
+        }
+        blob[next.0][next.1] = true;
+        frontier.push(next);
+        grown += 1;
+        failures = 0;
+    }
+    blob
+}
+
+// The outline of `blob` as corner points in order, walking clockwise.
+fn outline(blob: &[Vec<bool>]) -> Vec<(usize, usize)> {
+    let inside = |y: usize, x: usize, dy: isize, dx: isize| blob[(y as isize + dy) as usize][(x as isize + dx) as usize];
+    let mut next: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    for (y, row) in blob.iter().enumerate() {
+        for x in (0..row.len()).filter(|&x| row[x]) {
+            if !inside(y, x, -1, 0) { next.insert((y, x), (y, x + 1)); }
+            if !inside(y, x, 0, 1) { next.insert((y, x + 1), (y + 1, x + 1)); }
+            if !inside(y, x, 1, 0) { next.insert((y + 1, x + 1), (y + 1, x)); }
+            if !inside(y, x, 0, -1) { next.insert((y + 1, x), (y, x)); }
+        }
+    }
+
+    let start = *next.keys().min().unwrap();
+    let mut points = vec![start];
+    while let Some(&point) = next.get(points.last().unwrap()).filter(|&&point| point != start) {
+        points.push(point);
+    }
+    assert_eq!(points.len(), next.len(), "the blob's outline is a single loop");
+    points
+}
+
+// The pipe joining the tiles `from` and `to` through `tile`.
+fn pipe(from: (usize, usize), tile: (usize, usize), to: (usize, usize)) -> u8 {
+    let side = |(y, x): (usize, usize)| match (y.cmp(&tile.0), x.cmp(&tile.1)) {
+        (std::cmp::Ordering::Less, _)    => 'N',
+        (std::cmp::Ordering::Greater, _) => 'S',
+        (_, std::cmp::Ordering::Less)    => 'W',
+        _                                => 'E',
+    };
+    match (side(from), side(to)) {
+        ('N', 'S') | ('S', 'N') => b'|',
+        ('E', 'W') | ('W', 'E') => b'-',
+        ('N', 'E') | ('E', 'N') => b'L',
+        ('N', 'W') | ('W', 'N') => b'J',
+        ('S', 'W') | ('W', 'S') => b'7',
+        _                       => b'F',
+    }
+}
+
+// A field with one pipe loop around a random blob of `size` cells, scaled so
+// each cell is 2x2 tiles, amid scattered junk pipes. Its loop is twice the
+// blob's perimeter `p` long, so the farthest tile is `p` steps away, and by
+// Pick's theorem it encloses `4 * size - p + 1` tiles.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let side = 2 * (size.max(1) as f64).sqrt().ceil() as usize + 3;
+    let blob = blob(&mut rng, size.max(1), side);
+    let (cells, corners) = (blob.iter().flatten().filter(|&&cell| cell).count(), outline(&blob));
+
+    let tiles = 2 * side + 3;
+    let mut field: Vec<Vec<u8>> = (0..tiles)
+        .map(|_| (0..tiles).map(|_| if rng.chance(1, 3) { *rng.pick(b"|-LJ7F") } else { b'.' }).collect())
+        .collect();
+
+    let path: Vec<(usize, usize)> = corners.iter().zip(corners.iter().cycle().skip(1))
+        .flat_map(|(&(y, x), &(ny, nx))| [(2 * y + 1, 2 * x + 1), (y + ny + 1, x + nx + 1)])
+        .collect();
+    for (i, &tile) in path.iter().enumerate() {
+        field[tile.0][tile.1] = pipe(path[(i + path.len() - 1) % path.len()], tile, path[(i + 1) % path.len()]);
+    }
+
+    // Only the loop may connect to the start.
+    let start = *rng.pick(&path);
+    for (dy, dx) in AROUND.iter().step_by(2) {
+        let (y, x) = ((start.0 as isize + dy) as usize, (start.1 as isize + dx) as usize);
+        if !path.contains(&(y, x)) {
+            field[y][x] = b'.';
+        }
+    }
+    field[start.0][start.1] = b'S';
+
+    let input = field.iter().map(|row| format!("{}\n", String::from_utf8_lossy(row))).collect();
+    Generated::new(input, corners.len(), 4 * cells - corners.len() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day10;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day10, &generate(seed, 60));
+        }
+    }
+
+    #[test]
+    fn a_single_cell_encloses_one_tile() {
+        let generated = generate(0, 1);
+        assert_eq!(generated.answers, [Some(4.into()), Some(1.into())]);
+    }
+}
@@ -1,187 +1,223 @@
-use std::{error::Error, collections::VecDeque};
+use std::collections::{HashMap, VecDeque};
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
+use aoc_common::{checked, event, geometry::{self, Point}, graph::{Graph, NodeId}, grid::{Pos, SIDES}, trace::{self, Level}, Answer, Error, Grid, Result, Solution, Source};
+
+pub mod generate;
+
+const TILES: &str = "|-LJ7F.S";
+
+pub use aoc_common::read_file;
+
+pub struct Maze {
+    pub map: Grid<char>,
+    pub start: Pos,
+    // A node per tile the start is joined to through pipes, the start first,
+    // with edges to the tiles each one's pipe joins.
+    pub pipes: Graph<Pos>,
 }
-pub fn part_1(contents: &str) -> Option<usize> {
-    let map: Vec<Vec<char>> = contents.lines()
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    
-    let mut start_pos: Option<(usize, usize)> = None;
-    if let Some(row) = map.iter().position(|line| line.contains(&'S')) {
-        if let Some(col) = map[row].iter().position(|&c| c == 'S') {
-            start_pos = Some((row, col));
-        }
-    }
-    let start_pos = match start_pos {
-        Some(pos)   => pos,
-        None        => panic!("Must have been the wind...")
-    };
-
-    let mut stack: VecDeque<(usize, usize)> = VecDeque::new();
-    let mut path: Vec<(usize, usize)> = Vec::new();
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; map[0].len()]; map.len()];
-    visited[start_pos.0][start_pos.1] = true;
-
-    stack.push_back(start_pos);
-
-    while let Some(current) = stack.pop_front() {
-        path.push(start_pos);
-        let (y, x) = (current.0 as isize, current.1 as isize);
-        let edges = match map[current.0][current.1] {
-            'S' => vec![(y-1,x), (y+1,x), (y,x-1), (y,x+1)],
-            '-' => vec![(y,x-1), (y,x+1)], 
-            '|' => vec![(y-1,x), (y+1,x)], 
-            'L' => vec![(y-1,x), (y,x+1)], 
-            'J' => vec![(y-1,x), (y,x-1)], 
-            '7' => vec![(y+1,x), (y,x-1)], 
-            'F' => vec![(y+1,x), (y,x+1)], 
-            '.' => vec![], 
-            _ => panic!("Must have been the wind..."),
-        };
-        let edges: Vec<(usize, usize)> = edges.iter()
-            .filter_map(|&(y, x)| if y>= 0 && x>=0 { Some((y as usize, x as usize))} else {None})
-            .collect();
-
-        let dir_vecs: Vec<(isize, isize)> = edges.iter()
-            .map(|&(iy, ix)| ((iy as isize)-y, (ix as isize)-x))
-            .collect();
-
-        let dir_vecs: Vec<bool> = dir_vecs.iter()
-            .map(|&(iy, ix)| {
-                let (y, x): (isize, isize) = (current.0 as isize, current.1 as isize);
-                let tile: char = map[(y+iy) as usize][(x+ix) as usize];
-                match (iy, ix) {
-                    (1, 0) => ['|','L','J'].contains(&tile),
-                    (-1,0) => ['|','7','F'].contains(&tile),
-                    (0, 1) => ['-','J','7'].contains(&tile),
-                    (0,-1) => ['-','L','F'].contains(&tile),
-                    _ => panic!("Must have been the wind..."),
-                }
-            }).collect();
-
-        let valid_edges: Vec<(usize, usize)> = edges.iter().zip(dir_vecs.iter())
-            .filter_map(|(&c, &m)| if m { Some(c) } else { None })
-            .collect();
-        
-        for next in valid_edges {
-            if !visited[next.0][next.1] {
-                visited[next.0][next.1] = true;
-                stack.push_back(next);
-            }
-        }
+
+// The start's node in `Maze::pipes`.
+const START: NodeId = 0;
+
+// The steps a tile's pipe leads out along; the start may lead anywhere.
+fn exits(tile: char) -> &'static [(isize, isize)] {
+    match tile {
+        'S' => &SIDES,
+        '-' => &[(0,-1), (0, 1)],
+        '|' => &[(-1,0), (1, 0)],
+        'L' => &[(-1,0), (0, 1)],
+        'J' => &[(-1,0), (0,-1)],
+        '7' => &[(1, 0), (0,-1)],
+        'F' => &[(1, 0), (0, 1)],
+        '.' => &[],
+        _ => unreachable!("tiles are validated while parsing"),
     }
+}
 
-    Some(path.len() / 2)
+// The tiles next to `current` that its pipe and theirs connect it to; the
+// start connects to any pipe that leads into it.
+fn valid_edges(map: &Grid<char>, current: Pos) -> Vec<Pos> {
+    exits(map[current]).iter()
+        .filter_map(|&step| map.step(current, step).map(|next| (step, map[next], next)))
+        .filter(|&(step, tile, _)| match step {
+            (1, 0) => ['|','L','J','S'].contains(&tile),
+            (-1,0) => ['|','7','F','S'].contains(&tile),
+            (0, 1) => ['-','J','7','S'].contains(&tile),
+            (0,-1) => ['-','L','F','S'].contains(&tile),
+            _      => unreachable!("pipes lead out along the four sides only"),
+        })
+        .map(|(_, _, next)| next)
+        .collect()
 }
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let map: Vec<Vec<char>> = contents.lines()
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    
-    let mut start_pos: Option<(usize, usize)> = None;
-    if let Some(row) = map.iter().position(|line| line.contains(&'S')) {
-        if let Some(col) = map[row].iter().position(|&c| c == 'S') {
-            start_pos = Some((row, col));
+// The loop's tiles as the vertices of a polygon.
+fn polygon(path: &[Pos]) -> Vec<Point> {
+    path.iter().map(|&(y, x)| (x as i64, y as i64)).collect()
+}
+
+// The graph of the tiles the start is joined to, found breadth first from
+// it, so that junk pipes elsewhere on the map cost nothing.
+fn pipes(map: &Grid<char>, start: Pos) -> Graph<Pos> {
+    let mut pipes = Graph::new();
+    let mut ids = HashMap::from([(start, pipes.add_node(start))]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(pos) = queue.pop_front() {
+        for next in valid_edges(map, pos) {
+            let id = *ids.entry(next).or_insert_with(|| {
+                queue.push_back(next);
+                pipes.add_node(next)
+            });
+            pipes.add_edge(ids[&pos], id);
         }
     }
-    let start_pos = match start_pos {
-        Some(pos)   => pos,
-        None        => panic!("Must have been the wind...")
-    };
-
-    let mut stack: VecDeque<(usize, usize)> = VecDeque::new();
-    let mut path: Vec<(usize, usize)> = Vec::new();
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; map[0].len()]; map.len()];
-    visited[start_pos.0][start_pos.1] = true;
-
-    stack.push_back(start_pos);
-
-    while let Some(current) = stack.pop_back() {
-        path.push(current);
-        let (y, x) = (current.0 as isize, current.1 as isize);
-        let edges = match map[current.0][current.1] {
-            'S' => vec![(y-1,x), (y+1,x), (y,x-1), (y,x+1)],
-            '-' => vec![(y,x-1), (y,x+1)], 
-            '|' => vec![(y-1,x), (y+1,x)], 
-            'L' => vec![(y-1,x), (y,x+1)], 
-            'J' => vec![(y-1,x), (y,x-1)], 
-            '7' => vec![(y+1,x), (y,x-1)], 
-            'F' => vec![(y+1,x), (y,x+1)], 
-            '.' => vec![], 
-            _ => panic!("Must have been the wind..."),
-        };
-        let edges: Vec<(usize, usize)> = edges.iter()
-            .filter_map(|&(y, x)| if y>= 0 && x>=0 { Some((y as usize, x as usize))} else {None})
-            .collect();
-
-        // Validate edges/positions
-        let dir_vecs: Vec<(isize, isize)> = edges.iter()
-            .map(|&(iy, ix)| ((iy as isize)-y, (ix as isize)-x))
-            .collect();
-
-        let dir_vecs: Vec<bool> = dir_vecs.iter()
-            .map(|&(iy, ix)| {
-                let (y, x): (isize, isize) = (current.0 as isize, current.1 as isize);
-                let tile: char = map[(y+iy) as usize][(x+ix) as usize];
-                match (iy, ix) {
-                    (1, 0) => ['|','L','J'].contains(&tile),
-                    (-1,0) => ['|','7','F'].contains(&tile),
-                    (0, 1) => ['-','J','7'].contains(&tile),
-                    (0,-1) => ['-','L','F'].contains(&tile),
-                    _ => panic!("Must have been the wind..."),
+    pipes
+}
+
+// The tiles of the loop through the start, in order along it. A depth-first
+// search goes all the way round before it could turn back, but only if the
+// start's pipes form a plain loop: two of them leave the start, every tile
+// on the way joins exactly two others, and the last one leads back in.
+fn trace_loop(maze: &Maze) -> Result<Vec<Pos>> {
+    let order = maze.pipes.dfs(START).order;
+    trace::count("day10.nodes", order.len() as u64);
+
+    let looped = order.iter().all(|&node| maze.pipes.edges(node).len() == 2)
+        && order.last().is_some_and(|&last| maze.pipes.neighbors(last).any(|node| node == START));
+    if !looped {
+        return Err(Error::Unsolvable("the pipes through S do not form a loop".to_string()));
+    }
+    Ok(order.into_iter().map(|node| *maze.pipes.node(node)).collect())
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Maze;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        let source = Source::new(contents);
+
+        for line in source.lines() {
+            for (i, c) in line.char_indices() {
+                if !TILES.contains(c) {
+                    return Err(source.error(&line[i..i + c.len_utf8()], format!("a tile (one of {})", TILES)));
                 }
-            }).collect();
-
-        let valid_edges: Vec<(usize, usize)> = edges.iter().zip(dir_vecs.iter())
-            .filter_map(|(&c, &m)| if m { Some(c) } else { None })
-            .collect();
-        
-        for next in valid_edges {
-            if !visited[next.0][next.1] {
-                visited[next.0][next.1] = true;
-                stack.push_back(next);
             }
         }
+        let map = Grid::parse(&source)?;
+
+        let start = map.find(&'S').ok_or_else(|| source.error(source.end(), "a start tile 'S'"))?;
+
+        let pipes = pipes(&map, start);
+
+        Ok(Maze { map, start, pipes })
     }
-    fn shoelace_formula(vertices: &Vec<(usize, usize)>) -> f32 {
-        let mut sum: isize = 0;
 
-        for i in 0..vertices.len()-1 {
-            sum += (vertices[i].1 * vertices[i+1].0) as isize
-                 - (vertices[i+1].1 * vertices[i].0) as isize;
-        }
-        sum += (vertices[vertices.len()-1].1 * vertices[0].0) as isize
-             - (vertices[0].1 * vertices[vertices.len()-1].0) as isize;
+    fn part_1(&self, maze: &Self::Input) -> Result<Answer> {
+        let search = maze.pipes.bfs(START);
+        trace::count("day10.nodes", search.order.len() as u64);
+        event!(Level::Info, "day10.loop", length = search.order.len());
 
-        (sum as f32).abs() / 2.0
+        // The farthest tile along the loop, whichever way round.
+        Ok(search.distance.iter().flatten().max().copied().unwrap_or(0).into())
+    }
+
+    fn part_2(&self, maze: &Self::Input) -> Result<Answer> {
+        let path = trace_loop(maze)?;
+
+        // Pick's Theorem; a plain loop never crosses itself, so it is simple.
+        let polygon = polygon(&path);
+        let inside = geometry::interior_points(&polygon)?;
+        event!(Level::Info, "day10.loop", length = path.len(), inside = inside);
+        usize::try_from(inside).map(|inside| inside.into()).map_err(|_| checked::overflow())
     }
-    
-    // Pick's Theorem
-    let area = shoelace_formula(&path);
-    Some((area - path.len() as f32 / 2.0  + 1.0) as usize)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
+    use aoc_common::{fixture, property::{self, ensure}};
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test-1.dat").unwrap();
+        let input: String = read_file(fixture!("test-1.dat")).unwrap();
+        let input = Day10.parse(&input).unwrap();
 
-        let result = part_1(&input).unwrap();
-        assert_eq!(result, 8);
+        let result = Day10.part_1(&input).unwrap();
+        assert_eq!(result, 8.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test-2.dat").unwrap();
+        let input: String = read_file(fixture!("test-2.dat")).unwrap();
+        let input = Day10.parse(&input).unwrap();
+
+        let result = Day10.part_2(&input).unwrap();
+        assert_eq!(result, 10.into());
+    }
+
+    #[test]
+    fn unknown_tile_is_located() {
+        let input = "..F7.\n.FJ|.\nSJ.X7\n";
+
+        match Day10.parse(input) {
+            Err(aoc_common::Error::Parse(err)) => {
+                assert_eq!((err.line, err.column), (3, 4));
+                assert_eq!(err.found, "X");
+            },
+            other => panic!("unexpected {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn only_plain_loops_are_solved() {
+        // A third pipe into S, and a path from S that dead-ends both ways.
+        for input in [".....\n.F-7.\n-S.|.\n.L-J.\n.....\n", ".....\n.S-7.\n.|.|.\n.L-..\n.....\n", "S.\n..\n"] {
+            let maze = Day10.parse(input).unwrap();
+            assert!(matches!(Day10.part_2(&maze), Err(Error::Unsolvable(_))), "{}", input);
+        }
+
+        // Only the tiles joined to S are in the graph.
+        let maze = Day10.parse(".....\n.S-7.\n.|.|.\n.L-J.\n..F-7\n").unwrap();
+        assert_eq!(maze.pipes.len(), 8);
+        assert_eq!(Day10.part_2(&maze).unwrap(), 1.into());
+    }
 
-        let result = part_2(&input).unwrap();
-        assert_eq!(result, 10);
+    // The tiles inside the loop counted row by row, without Pick's theorem:
+    // a tile is inside once an odd number of the loop's north-facing pipes
+    // lie to its left.
+    fn enclosed(maze: &Maze, path: &[Pos]) -> usize {
+        let on_loop: HashSet<Pos> = path.iter().copied().collect();
+        let (y, x) = maze.start;
+        let start_north = y > 0 && [path[1], path[path.len() - 1]].contains(&(y - 1, x));
+
+        let mut count = 0;
+        for (y, row) in maze.map.rows().enumerate() {
+            let mut inside = false;
+            for (x, &tile) in row.iter().enumerate() {
+                match on_loop.contains(&(y, x)) {
+                    true  => inside ^= matches!(tile, '|' | 'L' | 'J') || (tile == 'S' && start_north),
+                    false => count += inside as usize,
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn loops_satisfy_picks_theorem() {
+        // A loop through `b` tiles around `i` tiles encloses an area of
+        // `i + b / 2 - 1`, whatever its shape.
+        property::check("day10 Pick's theorem", 200, |rng, size| {
+            let maze = Day10.parse(&generate::generate(rng.next_u64(), size).input).unwrap();
+            let path = trace_loop(&maze).map_err(|err| err.to_string())?;
+            let (twice_area, inside) = (geometry::twice_area(&polygon(&path)).unwrap(), enclosed(&maze, &path));
+
+            ensure(twice_area == (2 * inside + path.len() - 2) as u128, || format!("area {}/2 around {} tiles along {}", twice_area, inside, path.len()))?;
+            let answer = Day10.part_2(&maze).map_err(|err| err.to_string())?;
+            ensure(answer == inside.into(), || format!("part 2 says {}, the rows {}", answer, inside))
+        });
     }
 }
@@ -0,0 +1,55 @@
+use aoc_common::generate::{Generated, Rng};
+
+use crate::BAG;
+
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+// `size` games of one to six draws, each showing up to 20 cubes of some of
+// the colours; roughly half the games fit the bag.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let (mut input, mut part_1, mut part_2) = (String::new(), 0, 0);
+
+    for id in 1..=size {
+        let limit = if rng.chance(1, 2) { BAG } else { [20; 3] };
+        let mut fewest = [0; 3];
+        let mut draws: Vec<String> = Vec::new();
+
+        for _ in 0..rng.range(1..7) {
+            let mut colours = [0, 1, 2];
+            rng.shuffle(&mut colours);
+            let shown = &colours[..rng.range(1..4)];
+            let draw: Vec<String> = shown.iter()
+                .map(|&colour| {
+                    let count = rng.range(1..limit[colour] + 1);
+                    fewest[colour] = fewest[colour].max(count);
+                    format!("{} {}", count, COLOURS[colour])
+                })
+                .collect();
+            draws.push(draw.join(", "));
+        }
+
+        if fewest.iter().zip(BAG).all(|(&count, bag)| count <= bag) {
+            part_1 += id;
+        }
+        part_2 += fewest.iter().product::<usize>();
+        input += &format!("Game {}: {}\n", id, draws.join("; "));
+    }
+
+    Generated::new(input, part_1, part_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day2;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day2, &generate(seed, 100));
+        }
+    }
+}
@@ -1,113 +1,186 @@
-use std::error::Error;
+use std::io::BufRead;
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
-}
+use aoc_common::{checked, stream, Answer, Result, Solution, Source, Streaming};
+
+pub mod generate;
+
+pub use aoc_common::read_file;
 
 #[derive(Debug)]
-enum Cube {
+pub enum Cube {
     Red(usize),
     Green(usize),
     Blue(usize)
 }
 
-pub fn part_1(contents: &str) -> Option<usize> {
-    let mut games: Vec<(usize, Vec<Vec<Cube>>)> = Vec::new();
-
-    for line in contents.lines() {
-        let (id, round) = line.split_once(": ").unwrap();
-        let id: usize = id.split_once(" ").unwrap().1.parse().expect("Failed to parse Game ID");
-        let turns: Vec<Vec<Cube>> = round.split("; ")
-            .map(|turn| turn.split(", ")
-                 .map(|phase| {
-                     let action: Vec<&str> = phase.split(" ").collect();
-                     match action[1] {
-                         "red"      => Cube::Red(action[0].parse().unwrap()),
-                         "green"    => Cube::Green(action[0].parse().unwrap()),
-                         "blue"     => Cube::Blue(action[0].parse().unwrap()),
-                         _ => panic!("Must have been the wind..."),
-                     }
-                 }).collect::<Vec<Cube>>()
-            ).collect();
-
-        games.push((id, turns));
-    }   
-
-    let results = games.iter().map(|(id, turns)| {
-        let valid: bool = turns.iter()
-            .map(|turn| turn.iter().map(|phase| {
-                match phase {
-                    Cube::Red(v)      => if v <= &12 { true } else { false },
-                    Cube::Green(v)    => if v <= &13 { true } else { false },
-                    Cube::Blue(v)     => if v <= &14 { true } else { false }
-                }
-            }).collect::<Vec<bool>>().iter().all(|&v| v)
-        ).all(|v| v);
-        if valid { *id } else { 0 }
-    });
+type Game = (usize, Vec<Vec<Cube>>);
 
-    Some(results.sum())
+fn game(source: Source) -> Result<Game> {
+    let (id, round) = source.split_once(source.contents(), ": ")?;
+    let id: usize = source.number(source.strip_prefix(id, "Game ")?)?;
+    let turns: Vec<Vec<Cube>> = round.split("; ")
+        .map(|turn| turn.split(", ")
+             .map(|phase| {
+                 let (count, colour) = source.split_once(phase, " ")?;
+                 match colour {
+                     "red"      => Ok(Cube::Red(source.number(count)?)),
+                     "green"    => Ok(Cube::Green(source.number(count)?)),
+                     "blue"     => Ok(Cube::Blue(source.number(count)?)),
+                     _ => Err(source.error(colour, "a cube colour (red, green or blue)")),
+                 }
+             }).collect::<Result<Vec<Cube>>>()
+        ).collect::<Result<_>>()?;
+
+    Ok((id, turns))
 }
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let mut games: Vec<(usize, Vec<Vec<Cube>>)> = Vec::new();
-
-    for line in contents.lines() {
-        let (id, round) = line.split_once(": ").unwrap();
-        let id: usize = id.split_once(" ").unwrap().1.parse().expect("Failed to parse Game ID");
-        let turns: Vec<Vec<Cube>> = round.split("; ")
-            .map(|turn| turn.split(", ")
-                 .map(|phase| {
-                     let action: Vec<&str> = phase.split(" ").collect();
-                     match action[1] {
-                         "red"      => Cube::Red(action[0].parse().unwrap()),
-                         "green"    => Cube::Green(action[0].parse().unwrap()),
-                         "blue"     => Cube::Blue(action[0].parse().unwrap()),
-                         _ => panic!("Must have been the wind..."),
-                     }
-                 }).collect::<Vec<Cube>>()
-            ).collect();
-
-        games.push((id, turns));
-    }   
-    
-    let mut results: Vec<Vec<usize>> = Vec::new();
-
-    for i in 0..games.len() {
-        let (_, turns) = &games[i];
-        let mut max: Vec<usize> = vec![0, 0, 0];
-        for turn in turns {
-            for phase in turn {
-                match phase {
-                    Cube::Red(v)    => if v > &max[0] { max[0] = *v },
-                    Cube::Green(v)  => if v > &max[1] { max[1] = *v },
-                    Cube::Blue(v)   => if v > &max[2] { max[2] = *v }
-                }
+// The bag of part 1: 12 red, 13 green and 14 blue cubes.
+const BAG: [usize; 3] = [12, 13, 14];
+
+// Whether every draw of the game fits in a bag of `bag` red, green and blue cubes.
+fn feasible((_, turns): &Game, bag: [usize; 3]) -> bool {
+    turns.iter()
+        .all(|turn| turn.iter().all(|phase| {
+            match phase {
+                Cube::Red(v)      => v <= &bag[0],
+                Cube::Green(v)    => v <= &bag[1],
+                Cube::Blue(v)     => v <= &bag[2]
+            }
+        }))
+}
+
+// The game's id if it was possible with the bag of part 1.
+fn possible(game: &Game) -> usize {
+    if feasible(game, BAG) { game.0 } else { 0 }
+}
+
+// The fewest red, green and blue cubes that make the game possible.
+fn fewest((_, turns): &Game) -> [usize; 3] {
+    let mut max: [usize; 3] = [0, 0, 0];
+    for turn in turns {
+        for phase in turn {
+            match phase {
+                Cube::Red(v)    => if v > &max[0] { max[0] = *v },
+                Cube::Green(v)  => if v > &max[1] { max[1] = *v },
+                Cube::Blue(v)   => if v > &max[2] { max[2] = *v }
             }
         }
-        results.push(max.clone());
     }
-    Some(results.iter().map(|game| game.iter().product::<usize>()).sum())
+    max
+}
+
+fn power(game: &Game) -> Result<usize> {
+    checked::product(fewest(game))
+}
+
+pub struct Day2;
+
+impl Day2 {
+    // Games parsed one line at a time from any reader.
+    pub fn games(reader: impl BufRead, path: &str) -> impl Iterator<Item = Result<Game>> {
+        stream::parse_lines(reader, path, game)
+    }
+}
+
+impl Solution for Day2 {
+    type Input = Vec<Game>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        Self::games(contents.as_bytes(), stream::IN_MEMORY).collect()
+    }
+
+    fn part_1(&self, games: &Self::Input) -> Result<Answer> {
+        Ok(checked::sum(games.iter().map(possible))?.into())
+    }
+
+    fn part_2(&self, games: &Self::Input) -> Result<Answer> {
+        Ok(checked::try_sum(games.iter().map(power))?.into())
+    }
+}
+
+impl Streaming for Day2 {
+    fn stream_part_1(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        Ok(checked::try_sum(Self::games(reader, path).map(|game| game.map(|game| possible(&game))))?.into())
+    }
+
+    fn stream_part_2(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        Ok(checked::try_sum(Self::games(reader, path).map(|game| game.and_then(|game| power(&game))))?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{fixture, property::{self, ensure}};
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day2.parse(&input).unwrap();
 
-        let result = part_1(&input);
-        assert_eq!(result.unwrap(), 8);
+        let result = Day2.part_1(&input);
+        assert_eq!(result.unwrap(), 8.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day2.parse(&input).unwrap();
+
+        let result = Day2.part_2(&input);
+        assert_eq!(result.unwrap(), 2286.into());
+    }
+
+    #[test]
+    fn streaming_matches_parsed() {
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+
+        assert_eq!(Day2.stream_part_1(&mut input.as_bytes(), stream::IN_MEMORY).unwrap(), 8.into());
+        assert_eq!(Day2.stream_part_2(&mut input.as_bytes(), stream::IN_MEMORY).unwrap(), 2286.into());
+    }
+
+    #[test]
+    fn unknown_colour_is_located() {
+        let input = "Game 1: 3 blue, 4 red\nGame 2: 1 red, 2 purple\n";
+
+        match Day2.parse(input) {
+            Err(aoc_common::Error::Parse(err)) => {
+                assert_eq!((err.line, err.column), (2, 18));
+                assert_eq!(err.found, "purple");
+            },
+            other => panic!("unexpected {:?}", other.map(|_| ())),
+        }
+    }
 
-        let result = part_2(&input);
-        assert_eq!(result.unwrap(), 2286);
+    fn games(seed: u64, size: usize) -> Vec<Game> {
+        Day2.parse(&generate::generate(seed, size).input).unwrap()
+    }
+
+    #[test]
+    fn larger_bags_fit_every_game_a_smaller_one_does() {
+        property::check("day2 larger bags", 200, |rng, size| {
+            let bag = [rng.range(0..21), rng.range(0..21), rng.range(0..21)];
+            let larger = bag.map(|count| count + rng.range(0..5));
+            for game in games(rng.next_u64(), size) {
+                ensure(!feasible(&game, bag) || feasible(&game, larger), || format!("game {} fits {:?} but not {:?}", game.0, bag, larger))?;
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn the_fewest_cubes_are_just_enough() {
+        property::check("day2 fewest cubes", 200, |rng, size| {
+            for game in games(rng.next_u64(), size) {
+                let fewest = fewest(&game);
+                ensure(feasible(&game, fewest), || format!("game {} does not fit {:?}", game.0, fewest))?;
+                for colour in (0..3).filter(|&colour| fewest[colour] > 0) {
+                    let mut smaller = fewest;
+                    smaller[colour] -= 1;
+                    ensure(!feasible(&game, smaller), || format!("game {} fits {:?}, fewer than {:?}", game.0, smaller, fewest))?;
+                }
+            }
+            Ok(())
+        });
     }
 }
@@ -0,0 +1,90 @@
+use aoc_common::generate::{Generated, Rng};
+
+const SYMBOLS: &[u8] = b"#+$/@%=&-";
+
+// Blocks are laid out on a grid of cells this size, with a border of dots
+// so that nothing in one block touches another.
+const HEIGHT: usize = 5;
+const WIDTH: usize = 11;
+const PER_ROW: usize = 10;
+
+fn number(rng: &mut Rng) -> String {
+    let digits = rng.range(1..4);
+    rng.range(10_usize.pow(digits as u32 - 1)..10_usize.pow(digits as u32)).to_string()
+}
+
+// Writes `text` into the block's row `y` starting at column `x`.
+fn put(cells: &mut [Vec<u8>], y: usize, x: usize, text: &str) {
+    cells[y][x..x + text.len()].copy_from_slice(text.as_bytes());
+}
+
+// A schematic of `size` blocks. Each block either holds a symbol in its centre
+// with one to three numbers around it, or a number touching nothing. Only a
+// `*` is ever next to exactly two numbers, so every pair is a gear.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let rows = size.div_ceil(PER_ROW).max(1);
+    let mut cells = vec![vec![b'.'; PER_ROW * WIDTH]; rows * HEIGHT];
+    let (mut part_1, mut part_2) = (0, 0);
+
+    for block in 0..size {
+        let mut local = vec![vec![b'.'; WIDTH]; HEIGHT];
+
+        if rng.chance(1, 4) {
+            let text = number(&mut rng);
+            let y = rng.range(1..HEIGHT - 1);
+            put(&mut local, y, rng.range(1..WIDTH - text.len()), &text);
+        } else {
+            let gear = rng.chance(1, 2);
+            local[2][5] = if gear { b'*' } else { *rng.pick(SYMBOLS) };
+            let count = match gear {
+                true  => rng.range(1..4),
+                false => *rng.pick(&[1, 3]),
+            };
+
+            // Left, right, above and below the symbol, in a random order.
+            let mut sides = [0, 1, 2, 3];
+            rng.shuffle(&mut sides);
+            let mut values = Vec::new();
+            for &side in &sides[..count] {
+                let text = number(&mut rng);
+                let (y, x) = match side {
+                    0 => (2, 5 - text.len()),
+                    1 => (2, 6),
+                    2 => (1, rng.range(5 - text.len()..7)),
+                    _ => (3, rng.range(5 - text.len()..7)),
+                };
+                put(&mut local, y, x, &text);
+                values.push(text.parse::<usize>().unwrap());
+            }
+
+            part_1 += values.iter().sum::<usize>();
+            if gear && count == 2 {
+                part_2 += values[0] * values[1];
+            }
+        }
+
+        let (top, left) = (block / PER_ROW * HEIGHT, block % PER_ROW * WIDTH);
+        for (y, row) in local.iter().enumerate() {
+            cells[top + y][left..left + WIDTH].copy_from_slice(row);
+        }
+    }
+
+    let input = cells.iter().map(|row| format!("{}\n", String::from_utf8_lossy(row))).collect();
+    Generated::new(input, part_1, part_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day3;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day3, &generate(seed, 50));
+        }
+    }
+}
@@ -1,123 +1,116 @@
-use std::{error::Error, collections::HashSet};
+use std::collections::HashSet;
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
-}
+use aoc_common::{checked, grid::Pos, Answer, Grid, Result, Solution, Source};
 
-fn get_neighbors(pos: (isize, isize)) -> Vec<(Option<usize>, Option<usize>)> {
-    let radius: isize = 1;
-    let mut result = Vec::new();
+pub mod generate;
 
-    for y in (pos.0 - radius)..=(pos.0 + radius) {
-        for x in (pos.1 - radius)..=(pos.1 + radius) {
-            if (y, x) != pos {
-                result.push((y.try_into().ok(), x.try_into().ok()));
-            }
-        }
-    }
-    result
+pub use aoc_common::read_file;
+
+fn is_digit(grid: &Grid<char>, pos: Pos) -> bool {
+    grid.get(pos).is_some_and(|c| c.is_ascii_digit())
 }
 
-pub fn part_1(contents: &str) -> Option<usize> {
-    let grid: Vec<Vec<char>> = contents.lines().map(|line| line.chars().collect::<Vec<char>>()).collect();
-    let mut locs: HashSet<(usize, usize)> = HashSet::new();
-    
-    for (y, row) in grid.iter().enumerate() {
-        for (x, sym) in row.iter().enumerate() {
-            if sym.is_digit(10) || *sym == '.' {
-                continue;
-            }
-            
-            let neighbors = get_neighbors((y as isize, x as isize));
-            for neighbor in neighbors {
-                if neighbor.0.is_some() && neighbor.1.is_some()
-                    && grid[neighbor.0.unwrap()][neighbor.1.unwrap()].is_digit(10){
-                    let (ny, mut nx) = (neighbor.0.unwrap(), neighbor.1.unwrap());
-                    while nx > 0 && grid[ny][nx-1].is_digit(10) {
-                        nx = nx - 1;
-                    }
-                    locs.insert((ny, nx));
-                }
+// The first digits of the numbers next to the symbol at `pos`, each number
+// once however many of its digits touch the symbol.
+fn numbers_around(grid: &Grid<char>, pos: Pos) -> HashSet<Pos> {
+    grid.neighbors8(pos)
+        .filter(|&pos| is_digit(grid, pos))
+        .map(|mut pos| {
+            while let Some(left) = grid.step(pos, (0, -1)).filter(|&left| is_digit(grid, left)) {
+                pos = left;
             }
-        }
+            pos
+        })
+        .collect()
+}
+
+// The number whose first digit is at `(y, x)`.
+fn number_at(grid: &Grid<char>, (y, mut x): Pos) -> Result<usize> {
+    let mut value: usize = 0;
+    while let Some(digit) = grid.get((y, x)).and_then(|c| c.to_digit(10)) {
+        value = value.checked_mul(10)
+            .and_then(|value| value.checked_add(digit as usize))
+            .ok_or_else(checked::overflow)?;
+        x += 1;
     }
+    Ok(value)
+}
 
-    let result = locs.iter().map(|loc| {
-        let (y, mut x): (usize, usize) = (loc.0, loc.1);
-        let mut value: usize = 0;
-        while x < grid[y].len() && grid[y][x].is_digit(10) {
-            value = value * 10 + grid[y][x].to_digit(10).unwrap() as usize;
-            x += 1;
-        }
-        value
-    });
-
-    Some(result.sum())
+// Every cell holding neither a digit nor a '.'.
+fn symbols(grid: &Grid<char>) -> impl Iterator<Item = Pos> + '_ {
+    grid.cells()
+        .filter(|(_, sym)| !sym.is_ascii_digit() && **sym != '.')
+        .map(|(pos, _)| pos)
 }
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let grid: Vec<Vec<char>> = contents.lines().map(|line| line.chars().collect::<Vec<char>>()).collect();
-    let mut locs: Vec<HashSet<(usize, usize)>> = Vec::new();
-    
-    for (y, row) in grid.iter().enumerate() {
-        for (x, sym) in row.iter().enumerate() {
-            if sym.is_digit(10) || *sym == '.' {
-                continue;
-            }
-            
-            let neighbors = get_neighbors((y as isize, x as isize));
-            let mut test: HashSet<(usize, usize)> = HashSet::new();
-            for neighbor in neighbors {
-                if neighbor.0.is_some() && neighbor.1.is_some()
-                    && grid[neighbor.0.unwrap()][neighbor.1.unwrap()].is_digit(10){
-                    let (ny, mut nx) = (neighbor.0.unwrap(), neighbor.1.unwrap());
-                    while nx > 0 && grid[ny][nx-1].is_digit(10) {
-                        nx = nx - 1;
-                    }
-                    test.insert((ny, nx));
-                }
-            }
-            locs.push(test);
-        }
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Grid<char>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        Grid::parse(&Source::new(contents))
+    }
+
+    fn part_1(&self, grid: &Self::Input) -> Result<Answer> {
+        let locs: HashSet<Pos> = symbols(grid)
+            .flat_map(|pos| numbers_around(grid, pos))
+            .collect();
+
+        let result = locs.iter().map(|&loc| number_at(grid, loc));
+
+        Ok(checked::try_sum(result)?.into())
     }
 
-    let locs = locs.iter().filter_map(|loc| if loc.len()==2 { Some(loc) } else { None });
+    fn part_2(&self, grid: &Self::Input) -> Result<Answer> {
+        let locs: Vec<HashSet<Pos>> = symbols(grid)
+            .map(|pos| numbers_around(grid, pos))
+            .collect();
 
-    let result = locs.map(|locs| {
-        let mut result: Vec<usize> = Vec::new();
-        for loc in locs {
-            let (y, mut x): (usize, usize) = (loc.0, loc.1);
-            let mut value: usize = 0;
-            while x < grid[y].len() && grid[y][x].is_digit(10) {
-                value = value * 10 + grid[y][x].to_digit(10).unwrap() as usize;
-                x += 1;
+        let locs = locs.iter().filter(|loc| loc.len() == 2);
+
+        let result = locs.map(|locs| {
+            let mut result: Vec<usize> = Vec::new();
+            for &loc in locs {
+                result.push(number_at(grid, loc)?);
             }
-            result.push(value);
-        }
-        result[0] * result[1]
-    });
+            checked::product(result)
+        });
 
-    Some(result.sum())
+        Ok(checked::try_sum(result)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::fixture;
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day3.parse(&input).unwrap();
 
-        let result = part_1(&input).unwrap();
-        assert_eq!(result, 4361);
+        let result = Day3.part_1(&input).unwrap();
+        assert_eq!(result, 4361.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day3.parse(&input).unwrap();
+
+        let result = Day3.part_2(&input).unwrap();
+        assert_eq!(result, 467835.into());
+    }
+
+    #[test]
+    fn ragged_rows_and_huge_numbers_are_not_fatal() {
+        assert!(matches!(Day3.parse("12\n.*.\n..\n"), Err(aoc_common::Error::Parse(_))));
+        let input = Day3.parse("12.\n.*.\n...\n").unwrap();
+        assert_eq!(Day3.part_1(&input).unwrap(), 12.into());
 
-        let result = part_2(&input).unwrap();
-        assert_eq!(result, 467835);
+        let input = Day3.parse("99999999999999999999*1\n").unwrap();
+        assert!(matches!(Day3.part_1(&input), Err(aoc_common::Error::Overflow)));
     }
 }
@@ -0,0 +1,56 @@
+use aoc_common::generate::{Generated, Rng};
+
+const WINNING: usize = 10;
+const NUMBERS: usize = 25;
+
+fn numbers(values: &[usize]) -> String {
+    values.iter().map(|value| format!("{:2}", value)).collect::<Vec<_>>().join(" ")
+}
+
+// `size` cards of ten winning numbers and 25 numbers you have, laid out like
+// the real ones. Half the cards win nothing and the rest one to three
+// copies, never past the end of the table, which keeps the number of copies
+// growing slowly however many cards there are.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    let mut matches = Vec::new();
+
+    for card in 1..=size {
+        let mut pool: Vec<usize> = (1..100).collect();
+        rng.shuffle(&mut pool);
+        let (winning, others) = pool.split_at(WINNING);
+
+        let won = if rng.chance(1, 2) { 0 } else { rng.range(1..4) }.min(size - card);
+        let mut have: Vec<usize> = winning[..won].iter().chain(&others[..NUMBERS - won]).copied().collect();
+        rng.shuffle(&mut have);
+
+        input += &format!("Card {:3}: {} | {}\n", card, numbers(winning), numbers(&have));
+        matches.push(won);
+    }
+
+    let part_1: usize = matches.iter().filter(|&&won| won > 0).map(|&won| 1 << (won - 1)).sum();
+    let mut copies = vec![1; size];
+    for (card, &won) in matches.iter().enumerate() {
+        for next in card + 1..=card + won {
+            copies[next] += copies[card];
+        }
+    }
+
+    Generated::new(input, part_1, copies.iter().sum::<usize>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day4;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day4, &generate(seed, 100));
+        }
+    }
+}
@@ -1,82 +1,117 @@
-use std::{error::Error, collections::HashMap};
+use std::{collections::VecDeque, io::BufRead};
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
-}
+use aoc_common::{checked, event, stream, trace::{self, Level}, Answer, Result, Solution, Source, Streaming};
 
-pub fn part_1(contents: &str) -> Option<usize> {
-    let parse_str = |val: &str| -> usize { val.parse::<usize>().expect("Failed at parsing! NaN") };
+pub mod generate;
 
-    let cards: Vec<(Vec<usize>, Vec<usize>)> = contents.lines()
-        .map(|line| {
-             let (winning, numbers) = line.split_once(':').unwrap().1.split_once(" | ").unwrap();
-             (winning.split_whitespace().map(|val| parse_str(val)).collect::<Vec<usize>>(),
-              numbers.split_whitespace().map(|val| parse_str(val)).collect::<Vec<usize>>())
-        }).collect();
-    
-    let overlapping: Vec<usize> = cards.iter()
-        .map(|(winning, numbers)|
-             numbers.iter().filter(|&x| winning.contains(x)).count()
-        ).collect();
+pub use aoc_common::read_file;
 
-    let result: usize = overlapping.iter()
-        .filter_map(|n| if *n == 0 { None } else { Some(n) })
-        .map(|n| (2 as usize).pow((n-1).try_into().unwrap())).sum();
+type Card = (Vec<usize>, Vec<usize>);
 
-    Some(result)
+fn card(source: Source) -> Result<Card> {
+    let (_, card) = source.split_once(source.contents(), ":")?;
+    let (winning, numbers) = source.split_once(card, " | ")?;
+    Ok((source.numbers(winning)?, source.numbers(numbers)?))
 }
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let parse_str = |val: &str| -> usize { val.parse::<usize>().expect("Failed at parsing! NaN") };
-    let cards: Vec<(Vec<usize>, Vec<usize>)> = contents.lines()
-        .map(|line| {
-             let (winning, numbers) = line.split_once(':').unwrap().1.split_once(" | ").unwrap();
-             (winning.split_whitespace().map(|val| parse_str(val)).collect::<Vec<usize>>(),
-              numbers.split_whitespace().map(|val| parse_str(val)).collect::<Vec<usize>>())
-        }).collect();
-    
-    let overlapping: Vec<usize> = cards.iter()
-        .map(|(winning, numbers)|
-             numbers.iter().filter(|&x| winning.contains(x)).count()
-        ).collect();
-    
-    let mut result: HashMap<usize, usize> = HashMap::new();
-    for i in 0..overlapping.len() {
-        result.insert(i, 1);
+fn matches((winning, numbers): &Card) -> usize {
+    numbers.iter().filter(|&x| winning.contains(x)).count()
+}
+
+fn points(matches: usize) -> Result<usize> {
+    if matches == 0 {
+        return Ok(0);
     }
-    
-    for (i, overlap) in overlapping.iter().enumerate() {
-        for _ in 0..*result.get(&i).expect("Failed at retrieving the number of cards! NaN") {
-            for j in 1..=*overlap {
-                match result.get_mut(&(i+j)) {
-                    Some(value) => *value += 1,
-                    None        => { result.insert(i+j, 1); () },
-                };
-            }
+    u32::try_from(matches - 1).ok().and_then(|power| 2_usize.checked_pow(power)).ok_or_else(checked::overflow)
+}
+
+// Total scratchcards once every card has won its copies of the cards after
+// it. Only the copies still owed to upcoming cards are kept, so memory is
+// bounded by the largest number of matches rather than the number of cards.
+fn total_cards(matches: impl Iterator<Item = Result<usize>>) -> Result<usize> {
+    let mut owed: VecDeque<usize> = VecDeque::new();
+    let mut total = 0;
+
+    for (index, overlap) in matches.enumerate() {
+        let overlap = overlap?;
+        let copies = owed.pop_front().unwrap_or(0).checked_add(1).ok_or_else(checked::overflow)?;
+        total = checked::sum([total, copies])?;
+        trace::count("day4.copies", (copies - 1) as u64);
+        event!(Level::Debug, "day4.card", card = index + 1, copies = copies, wins = overlap);
+        if owed.len() < overlap {
+            owed.resize(overlap, 0);
         }
+        for won in owed.iter_mut().take(overlap) {
+            *won = checked::sum([*won, copies])?;
+        }
+    }
+
+    Ok(total)
+}
+
+pub struct Day4;
+
+impl Day4 {
+    // Cards parsed one line at a time from any reader.
+    pub fn cards(reader: impl BufRead, path: &str) -> impl Iterator<Item = Result<Card>> {
+        stream::parse_lines(reader, path, card)
+    }
+}
+
+impl Solution for Day4 {
+    type Input = Vec<Card>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        Self::cards(contents.as_bytes(), stream::IN_MEMORY).collect()
+    }
+
+    fn part_1(&self, cards: &Self::Input) -> Result<Answer> {
+        Ok(checked::try_sum(cards.iter().map(|card| points(matches(card))))?.into())
+    }
+
+    fn part_2(&self, cards: &Self::Input) -> Result<Answer> {
+        Ok(total_cards(cards.iter().map(|card| Ok(matches(card))))?.into())
+    }
+}
+
+impl Streaming for Day4 {
+    fn stream_part_1(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        Ok(checked::try_sum(Self::cards(reader, path).map(|card| card.and_then(|card| points(matches(&card)))))?.into())
     }
 
-    Some(result.values().sum::<usize>())
+    fn stream_part_2(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        Ok(total_cards(Self::cards(reader, path).map(|card| card.map(|card| matches(&card))))?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::fixture;
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day4.parse(&input).unwrap();
 
-        let result = part_1(&input).unwrap();
-        assert_eq!(result, 13);
+        let result = Day4.part_1(&input).unwrap();
+        assert_eq!(result, 13.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day4.parse(&input).unwrap();
+
+        let result = Day4.part_2(&input).unwrap();
+        assert_eq!(result, 30.into());
+    }
+
+    #[test]
+    fn streaming_matches_parsed() {
+        let input: String = read_file(fixture!("test.dat")).unwrap();
 
-        let result = part_2(&input).unwrap();
-        assert_eq!(result, 30);
+        assert_eq!(Day4.stream_part_1(&mut input.as_bytes(), stream::IN_MEMORY).unwrap(), 13.into());
+        assert_eq!(Day4.stream_part_2(&mut input.as_bytes(), stream::IN_MEMORY).unwrap(), 30.into());
     }
 }
@@ -0,0 +1,113 @@
+use aoc_common::generate::{Generated, Rng};
+
+const MAPS: [&str; 7] = [
+    "seed-to-soil", "soil-to-fertilizer", "fertilizer-to-water", "water-to-light",
+    "light-to-temperature", "temperature-to-humidity", "humidity-to-location",
+];
+
+// `(destination, source, length)` lines, as in the almanac.
+type Map = Vec<(usize, usize, usize)>;
+
+// Cuts `0..span` into `pieces` ranges and moves them to shuffled places, so
+// every number maps to exactly one other. Ranges that stay put are left out,
+// as numbers nothing maps pass through unchanged anyway.
+fn map(rng: &mut Rng, span: usize, pieces: usize) -> Map {
+    let mut cuts: Vec<usize> = (0..pieces - 1).map(|_| rng.range(1..span)).collect();
+    cuts.extend([0, span]);
+    cuts.sort();
+    cuts.dedup();
+    let sources: Vec<(usize, usize)> = cuts.windows(2).map(|pair| (pair[0], pair[1] - pair[0])).collect();
+
+    let mut order: Vec<usize> = (0..sources.len()).collect();
+    rng.shuffle(&mut order);
+    let mut destination = 0;
+    let mut map = Map::new();
+    for index in order {
+        let (source, length) = sources[index];
+        if destination != source {
+            map.push((destination, source, length));
+        }
+        destination += length;
+    }
+    rng.shuffle(&mut map);
+    map
+}
+
+// Where `map` sends the numbers `start..end`, as ranges.
+fn map_range(map: &Map, (start, end): (usize, usize)) -> Vec<(usize, usize)> {
+    let mut mapped = Vec::new();
+    let mut rest = vec![(start, end)];
+
+    for &(destination, source, length) in map {
+        let mut outside = Vec::new();
+        for (start, end) in rest {
+            let (from, to) = (start.max(source), end.min(source + length));
+            if from >= to {
+                outside.push((start, end));
+                continue;
+            }
+            mapped.push((destination + from - source, destination + to - source));
+            outside.extend([(start, from), (to, end)].into_iter().filter(|(start, end)| start < end));
+        }
+        rest = outside;
+    }
+
+    mapped.extend(rest);
+    mapped
+}
+
+// An almanac whose seven maps each shuffle `size` ranges of numbers, with two
+// to five seed ranges. Some seeds lie past every map and pass straight
+// through. Part 2 grows with the seed ranges, which brute force walks one by
+// one, so they are kept to a few percent of the mapped numbers.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let span = 100 * size.max(1);
+    let maps: Vec<Map> = MAPS.iter().map(|_| map(&mut rng, span, size.max(1))).collect();
+
+    let seeds: Vec<(usize, usize)> = (0..rng.range(2..6))
+        .map(|_| (rng.range(0..span + span / 5), rng.range(1..span / 20 + 2)))
+        .collect();
+
+    let locate = |ranges: Vec<(usize, usize)>| maps.iter().fold(ranges, |ranges, map| {
+        ranges.into_iter().flat_map(|range| map_range(map, range)).collect()
+    });
+    let singles = seeds.iter().flat_map(|&(start, length)| [(start, start + 1), (length, length + 1)]).collect();
+    let part_1 = locate(singles).into_iter().map(|(start, _)| start).min().unwrap();
+    let pairs = seeds.iter().map(|&(start, length)| (start, start + length)).collect();
+    let part_2 = locate(pairs).into_iter().map(|(start, _)| start).min().unwrap();
+
+    let numbers: Vec<String> = seeds.iter().map(|(start, length)| format!("{} {}", start, length)).collect();
+    let mut input = format!("seeds: {}\n", numbers.join(" "));
+    for (name, map) in MAPS.iter().zip(&maps) {
+        input += &format!("\n{} map:\n", name);
+        for (destination, source, length) in map {
+            input += &format!("{} {} {}\n", destination, source, length);
+        }
+    }
+
+    Generated::new(input, part_1, part_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day5;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day5, &generate(seed, 10));
+        }
+    }
+
+    #[test]
+    fn maps_split_ranges() {
+        let map = vec![(50, 98, 2), (52, 50, 48)];
+        let mut mapped = map_range(&map, (45, 100));
+        mapped.sort();
+        assert_eq!(mapped, [(45, 50), (50, 52), (52, 100)]);
+    }
+}
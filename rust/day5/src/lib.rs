@@ -1,112 +1,151 @@
-use std::error::Error;
+use aoc_common::{budget, event, interval::{Interval, IntervalSet, PiecewiseMap, Unmapped}, trace::{self, Level}, Answer, Error, Implementation, Part, Result, Solution, Source};
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
+pub mod generate;
+
+pub use aoc_common::read_file;
+
+pub struct Almanac {
+    pub seeds: Vec<usize>,
+    pub maps: Vec<PiecewiseMap>,
 }
 
-pub fn part_1(contents: &str) -> Option<usize> {
-    let seeds: Vec<usize> = contents.split("\n\n").nth(0).unwrap()
-        .split_whitespace().skip(1).map(|v| v.parse().unwrap()).collect();
-    let directives: Vec<&str> = contents.split("\n\n").collect::<Vec<&str>>();
-
-    let mut almanac: Vec<Vec<(usize, usize, usize)>> = Vec::new();
-    for directive in directives.iter().skip(1) {
-        let set: Vec<(usize, usize, usize)> = directive.lines().skip(1)
-            .map(|line| line.split_whitespace()
-                 .map(|s| s.parse::<usize>().expect("Parsing directive failed! NaN"))
-                 .collect::<Vec<usize>>()
-                 .chunks(3)
-                 .map(|chunk| (chunk[0], chunk[1], chunk[2]))
-                 .collect::<Vec<(usize, usize, usize)>>()
-                ).flatten().collect();
-
-        almanac.push(set);
+impl Almanac {
+    fn locate(&self, mut seed: usize) -> usize {
+        for map in &self.maps {
+            let (mapped, hit) = map.map_with_hit(seed);
+            trace::count(if hit { "day5.map_hits" } else { "day5.map_misses" }, 1);
+            seed = mapped.expect("unmapped numbers pass through unchanged");
+        }
+        seed
     }
 
-    let mut final_dest: Vec<usize> = seeds.clone();
-    for i in 0..final_dest.len() {
-        for map in &almanac {
-            for (dest, start, range) in map {
-                let seed_range = start..&(start+range);
-                if !seed_range.contains(&&final_dest[i]) {
-                    continue;
-                }
-                let dist = seed_range.end - final_dest[i];
-                let res = (dest+range) - dist;
-
-                final_dest[i] = res;
-                break;
-            }
+    // Where every number in `seeds` ends up.
+    fn locate_set(&self, seeds: IntervalSet) -> IntervalSet {
+        self.maps.iter().fold(seeds, |numbers, map| map.map_set(&numbers))
+    }
+
+    // The seed numbers read as pairs of start and length.
+    fn seed_ranges(&self) -> Result<Vec<Interval>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(Error::Unsolvable("seeds do not pair up into ranges".to_string()));
         }
+        // Every range has to end within the numbers, or the loops over it overflow.
+        self.seeds.chunks(2).map(|chunk| Interval::from_length(chunk[0], chunk[1])).collect()
     }
+}
+
+// Part 2 without visiting every seed: the ranges are mapped whole.
+fn intervals_part_2(almanac: &Almanac) -> Result<Answer> {
+    let seeds: IntervalSet = almanac.seed_ranges()?.into_iter().collect();
 
-    Some(*final_dest.iter().min().unwrap())
+    almanac.locate_set(seeds).min()
+        .map(|location| location.into())
+        .ok_or_else(|| Error::Unsolvable("every seed range is empty".to_string()))
 }
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let seeds: Vec<_> = contents.split("\n\n").nth(0).unwrap()
-        .split_whitespace().skip(1).map(|v| v.parse().unwrap())
-        .collect::<Vec<usize>>()
-        .chunks(2).map(|chunk| (chunk[0], chunk[1]))
-        .collect::<Vec<(usize, usize)>>();
-    
-    let directives: Vec<&str> = contents.split("\n\n").collect::<Vec<&str>>();
-
-    let mut almanac: Vec<Vec<(usize, usize, usize)>> = Vec::new();
-    for directive in directives.iter().skip(1) {
-        let set: Vec<(usize, usize, usize)> = directive.lines().skip(1)
-            .map(|line| line.split_whitespace()
-                 .map(|s| s.parse::<usize>().expect("Parsing directive failed! NaN"))
-                 .collect::<Vec<usize>>()
-                 .chunks(3)
-                 .map(|chunk| (chunk[0], chunk[1], chunk[2]))
-                 .collect::<Vec<(usize, usize, usize)>>()
-                ).flatten().collect();
-
-        almanac.push(set);
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Almanac;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        let source = Source::new(contents);
+        let directives: Vec<&str> = contents.split("\n\n").collect::<Vec<&str>>();
+
+        let seeds: Vec<usize> = source.numbers(source.strip_prefix(directives[0], "seeds:")?)?;
+
+        let mut maps: Vec<PiecewiseMap> = Vec::new();
+        for directive in directives.iter().skip(1) {
+            let set: Vec<(Interval, usize)> = directive.lines().skip(1)
+                .map(|line| match *source.numbers::<usize>(line)?.as_slice() {
+                     [dest, start, range] if dest.max(start).checked_add(range).is_some() => Ok((Interval::new(start, start + range), dest)),
+                     [_, _, _] => Err(source.error(line, "a range that ends within the numbers")),
+                     _ => Err(source.error(line, "three numbers: destination, source and length")),
+                 })
+                .collect::<Result<_>>()?;
+
+            // Numbers no line maps pass through unchanged.
+            maps.push(PiecewiseMap::new(set, Unmapped::Identity)?);
+        }
+
+        Ok(Almanac { seeds, maps })
+    }
+
+    fn part_1(&self, almanac: &Self::Input) -> Result<Answer> {
+        let final_dest: Vec<usize> = almanac.seeds.iter()
+            .map(|&seed| {
+                let location = almanac.locate(seed);
+                event!(Level::Debug, "day5.seed", seed = seed, location = location);
+                location
+            })
+            .collect();
+
+        final_dest.iter().min()
+            .map(|&location| location.into())
+            .ok_or_else(|| Error::Unsolvable("the almanac lists no seeds".to_string()))
     }
-    let result: Vec<usize> = seeds.iter().map(|seeds| {
-        let mut final_dest: Vec<usize> = (seeds.0..(seeds.0+seeds.1)).collect();
-
-        for i in 0..final_dest.len() {
-            for map in &almanac {
-                for (dest, start, range) in map {
-                    let seed_range = start..&(start+range);
-                    if !seed_range.contains(&&final_dest[i]) {
-                        continue;
-                    }
-                    let dist = seed_range.end - final_dest[i];
-                    let res = (dest+range) - dist;
-
-                    final_dest[i] = res;
-                    break;
-                }
+
+    fn part_2(&self, almanac: &Self::Input) -> Result<Answer> {
+        let seeds = almanac.seed_ranges()?;
+
+        // Brute force over every seed, which can take hours on a real input;
+        // each seed is a step against the caller's budget.
+        let mut result: Option<usize> = None;
+        for range in &seeds {
+            let mut lowest: Option<usize> = None;
+            for seed in range.start..range.end {
+                budget::step()?;
+                let location = almanac.locate(seed);
+                lowest = Some(lowest.map_or(location, |min| min.min(location)));
+            }
+            if let Some(lowest) = lowest {
+                event!(Level::Info, "day5.range", start = range.start, length = range.len(), lowest = lowest);
+                result = Some(result.map_or(lowest, |min| min.min(lowest)));
             }
         }
-        *final_dest.iter().min().unwrap()
-    }).collect();
-    
-    Some(*result.iter().min().expect("Minimum value not found"))
+
+        result.map(|location| location.into())
+            .ok_or_else(|| Error::Unsolvable("every seed range is empty".to_string()))
+    }
+
+    fn implementations(&self) -> Vec<Implementation<Self::Input>> {
+        vec![
+            Implementation { name: "brute-force", part: Part::Two, solve: |almanac| Day5.part_2(almanac) },
+            Implementation { name: "intervals",   part: Part::Two, solve: intervals_part_2 },
+        ]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::fixture;
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day5.parse(&input).unwrap();
 
-        let result = part_1(&input).unwrap();
-        assert_eq!(result, 35);
+        let result = Day5.part_1(&input).unwrap();
+        assert_eq!(result, 35.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day5.parse(&input).unwrap();
+
+        let result = Day5.part_2(&input).unwrap();
+        assert_eq!(result, 46.into());
+    }
+
+    #[test]
+    fn intervals_match_brute_force() {
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day5.parse(&input).unwrap();
 
-        let result = part_2(&input).unwrap();
-        assert_eq!(result, 46);
+        assert_eq!(intervals_part_2(&input).unwrap(), 46.into());
+        let located = input.locate_set(IntervalSet::from_iter([Interval::new(79, 93)]));
+        assert_eq!((located.len(), located.min()), (14, Some(46)));
     }
 }
@@ -1,70 +1,176 @@
-use std::error::Error;
+use aoc_common::{budget, checked, Answer, Implementation, Part, Result, Solution, Source};
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
+pub mod generate;
+
+pub use aoc_common::read_file;
+
+// Tries every hold, each a step against the caller's budget; the products
+// are widened so that no race can overflow them.
+fn count_wins(time: usize, distance: usize) -> Result<usize> {
+    let mut score: usize = 0;
+    for i in 1..time {
+        budget::step()?;
+        if (i as u128 * (time - i) as u128) > distance as u128 {
+            score += 1;
+        }
+    }
+    Ok(score)
 }
 
-pub fn part_1(contents: &str) -> Option<usize> {
-    let mut lines = contents.lines();
+// The same count from the roots of `hold * (time - hold) = distance`: every
+// hold strictly between them wins. The square root is rounded down, so the
+// first winning hold is found by stepping from the estimate.
+fn closed_form_wins(time: usize, distance: usize) -> usize {
+    let (time, distance) = (time as u128, distance as u128);
+    if time * time <= 4 * distance {
+        return 0;
+    }
+    let beats = |hold: u128| hold * (time - hold) > distance;
+    let mut low = (time - (time * time - 4 * distance).isqrt()) / 2;
+    while low <= time / 2 && !beats(low) {
+        low += 1;
+    }
+    // Even the best hold, half the time, may fall short of the record.
+    if low > time / 2 {
+        return 0;
+    }
+    while beats(low - 1) {
+        low -= 1;
+    }
+    (time - 2 * low + 1) as usize
+}
 
-    let times: Vec<usize> = lines.next().unwrap()
-        .split_once(':').unwrap().1.split_whitespace()
-        .map(|s| s.parse().expect("Parse failed! NaN")).collect();
-    
-    let distances: Vec<usize> = lines.next().unwrap()
-        .split_once(':').unwrap().1.split_whitespace()
-        .map(|s| s.parse().expect("Parse failed! NaN")).collect();
-
-    let results = times.iter().zip(distances.iter()).map(|(time, distance)| {
-        let mut score: usize = 0;
-        for i in 1..*time {
-            if (i * (*time-i)) > *distance {
-                score += 1;
-            }
+// Part 2 ignores the spacing and reads each line as one long number.
+fn concat_digits(values: &[usize]) -> Result<usize> {
+    values.iter().try_fold(0_usize, |acc, &v| {
+        let mut shift: usize = 10;
+        while shift <= v {
+            shift = shift.checked_mul(10).ok_or_else(checked::overflow)?;
         }
-        score
-    });
-    
-    Some(results.product::<usize>())
+        acc.checked_mul(shift).and_then(|acc| acc.checked_add(v)).ok_or_else(checked::overflow)
+    })
 }
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let mut lines = contents.lines();
+pub struct Races {
+    pub times: Vec<usize>,
+    pub distances: Vec<usize>,
+}
 
-    let time: usize = lines.next().unwrap()
-        .split_once(':').unwrap().1.replace(" ", "").parse().expect("Parse failed! NaN");
-    
-    let distance: usize = lines.next().unwrap()
-        .split_once(':').unwrap().1.replace(" ", "").parse().expect("Parse failed! NaN");
+fn closed_form_part_1(races: &Races) -> Result<Answer> {
+    let results = races.times.iter().zip(races.distances.iter())
+        .map(|(&time, &distance)| closed_form_wins(time, distance));
 
-    let mut score: usize = 0;
-    for i in 1..time {
-        if (i * (time - i)) > distance {
-            score += 1;
+    Ok(checked::product(results)?.into())
+}
+
+fn closed_form_part_2(races: &Races) -> Result<Answer> {
+    Ok(closed_form_wins(concat_digits(&races.times)?, concat_digits(&races.distances)?).into())
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Races;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        let source = Source::new(contents);
+        let mut lines = source.lines();
+
+        let line = lines.next().ok_or_else(|| source.error(source.end(), "a \"Time:\" line"))?;
+        let times: Vec<usize> = source.numbers(source.strip_prefix(line, "Time:")?)?;
+
+        let line = lines.next().ok_or_else(|| source.error(source.end(), "a \"Distance:\" line"))?;
+        let distances: Vec<usize> = source.numbers(source.strip_prefix(line, "Distance:")?)?;
+
+        if times.len() != distances.len() {
+            return Err(source.error(line, format!("{} distances, one per race", times.len())));
         }
+
+        Ok(Races { times, distances })
     }
 
-    Some(score)
+    fn part_1(&self, races: &Self::Input) -> Result<Answer> {
+        let results = races.times.iter().zip(races.distances.iter())
+            .map(|(&time, &distance)| count_wins(time, distance));
+
+        Ok(checked::product(results.collect::<Result<Vec<usize>>>()?)?.into())
+    }
+
+    fn part_2(&self, races: &Self::Input) -> Result<Answer> {
+        let time: usize = concat_digits(&races.times)?;
+        let distance: usize = concat_digits(&races.distances)?;
+
+        Ok(count_wins(time, distance)?.into())
+    }
+
+    fn implementations(&self) -> Vec<Implementation<Self::Input>> {
+        vec![
+            Implementation { name: "loop",        part: Part::One, solve: |races| Day6.part_1(races) },
+            Implementation { name: "closed-form", part: Part::One, solve: closed_form_part_1 },
+            Implementation { name: "loop",        part: Part::Two, solve: |races| Day6.part_2(races) },
+            Implementation { name: "closed-form", part: Part::Two, solve: closed_form_part_2 },
+        ]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{fixture, property::{self, ensure}};
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day6.parse(&input).unwrap();
 
-        let result = part_1(&input).unwrap();
-        assert_eq!(result, 288);
+        let result = Day6.part_1(&input).unwrap();
+        assert_eq!(result, 288.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day6.parse(&input).unwrap();
 
-        let result = part_2(&input).unwrap();
-        assert_eq!(result, 71503);
+        let result = Day6.part_2(&input).unwrap();
+        assert_eq!(result, 71503.into());
+    }
+
+    #[test]
+    fn closed_form_matches_the_loop() {
+        for time in 0..40 {
+            for distance in 0..time * time / 4 + 2 {
+                assert_eq!(closed_form_wins(time, distance), count_wins(time, distance).unwrap(), "time {} distance {}", time, distance);
+            }
+        }
+    }
+
+    // A race of up to `size` thousand milliseconds and a record below the best
+    // distance, or now and then above it.
+    fn race(rng: &mut aoc_common::generate::Rng, size: usize) -> (usize, usize) {
+        let time = rng.range(0..1000 * size + 1);
+        (time, rng.range(0..time * time / 4 + 2))
+    }
+
+    #[test]
+    fn wins_are_symmetric_around_half_the_time() {
+        // Holding `h` goes as far as holding `time - h`, so the winning holds
+        // pair up around `time / 2`, leaving the middle one alone if `time` is even.
+        property::check("day6 symmetric wins", 100, |rng, size| {
+            let (time, distance) = race(rng, size);
+            let wins = count_wins(time, distance).unwrap();
+            let below = (1..time.div_ceil(2)).filter(|&hold| hold * (time - hold) > distance).count();
+            let middle = time % 2 == 0 && (time / 2) * (time / 2) > distance;
+            ensure(wins == 2 * below + middle as usize, || format!("{} wins in {}ms over {}mm, {} below half", wins, time, distance, below))
+        });
+    }
+
+    #[test]
+    fn closed_form_counts_as_the_loop_does() {
+        property::check("day6 closed form", 100, |rng, size| {
+            let (time, distance) = race(rng, size);
+            let (closed, counted) = (closed_form_wins(time, distance), count_wins(time, distance).unwrap());
+            ensure(closed == counted, || format!("{} against {} in {}ms over {}mm", closed, counted, time, distance))
+        });
     }
 }
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use aoc_common::generate::{Generated, Rng};
+
+use crate::CARDS;
+
+// How many of each card a hand holds, one shape per hand type.
+const SHAPES: [&[usize]; 7] = [&[5], &[4, 1], &[3, 2], &[3, 1, 1], &[2, 2, 1], &[2, 1, 1, 1], &[1, 1, 1, 1, 1]];
+
+// Orders hands under either rule: the type first, then card by card using
+// `order`, with jokers (if any) joining the largest group.
+fn strength(hand: &[u8], order: &[u8], jokers: bool) -> (Vec<usize>, Vec<usize>) {
+    let mut counts: Vec<usize> = order.iter()
+        .filter(|&&card| !(jokers && card == b'J'))
+        .map(|card| hand.iter().filter(|&c| c == card).count())
+        .filter(|&count| count > 0)
+        .collect();
+    counts.sort_by(|a, b| b.cmp(a));
+    if jokers {
+        let wild = hand.iter().filter(|&&c| c == b'J').count();
+        match counts.first_mut() {
+            Some(largest) => *largest += wild,
+            None          => counts.push(wild),
+        }
+    }
+    (counts, hand.iter().map(|card| order.iter().position(|c| c == card).unwrap()).collect())
+}
+
+fn winnings(hands: &[(Vec<u8>, usize)], order: &[u8], jokers: bool) -> usize {
+    let mut ranked: Vec<_> = hands.iter().map(|(hand, bid)| (strength(hand, order, jokers), bid)).collect();
+    ranked.sort();
+    ranked.iter().enumerate().map(|(rank, (_, &bid))| (rank + 1) * bid).sum()
+}
+
+// `size` distinct hands (at most 13^5) with bids up to 1000. Every type is
+// equally likely, so even five of a kind shows up in small inputs.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let size = size.min(13_usize.pow(5));
+    let mut seen = HashSet::new();
+    let mut hands = Vec::new();
+
+    while hands.len() < size {
+        let mut labels: Vec<u8> = CARDS.bytes().collect();
+        rng.shuffle(&mut labels);
+        let mut hand: Vec<u8> = rng.pick(&SHAPES).iter().zip(labels)
+            .flat_map(|(&count, label)| std::iter::repeat_n(label, count))
+            .collect();
+        rng.shuffle(&mut hand);
+        if seen.insert(hand.clone()) {
+            hands.push((hand, rng.range(1..1001)));
+        }
+    }
+
+    let input = hands.iter().map(|(hand, bid)| format!("{} {}\n", String::from_utf8_lossy(hand), bid)).collect();
+    let part_1 = winnings(&hands, CARDS.as_bytes(), false);
+    let part_2 = winnings(&hands, b"J23456789TQKA", true);
+    Generated::new(input, part_1, part_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day7;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day7, &generate(seed, 200));
+        }
+    }
+}
@@ -1,117 +1,141 @@
-use std::{error::Error, collections::HashMap};
+use std::collections::HashMap;
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
-}
+use aoc_common::{checked, stream, Answer, Result, Solution, Source};
 
-pub fn part_1(contents: &str) -> Option<usize> {
-    let tokens: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
-    let token_map: HashMap<char, usize> = tokens.iter().enumerate().map(|(i, &token)| (token, i)).collect();
-    
-    let hands: Vec<_> = contents.lines()
-        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
-        .map(|chunk| (chunk[0].chars().map(|s| *token_map.get(&s).unwrap()).collect::<Vec<usize>>(),
-                      chunk[1].parse::<usize>().expect("Parse failed! NaN")))
-        .collect();
-
-    let mut ranked: Vec<(usize, Vec<usize>, usize)> = Vec::new();
-    for (hand, bid) in &hands {
-        let mut counts: HashMap<usize, usize> = HashMap::new();
-        for &c in hand {
-            *counts.entry(c).or_insert(0) += 1;
-        }
-        let mut counts_vec = counts.values().cloned().collect::<Vec<usize>>();
-        counts_vec.sort_by(|a, b| b.cmp(a));
-        let rank: usize = match counts_vec.as_slice() {
-            [5]             => 7,
-            [4, 1]          => 6,
-            [3, 2]          => 5,
-            [3, 1, 1]       => 4,
-            [2, 2, 1]       => 3,
-            [2, 1, 1, 1]    => 2,
-            [1, 1, 1, 1, 1] => 1,
-            _               => panic!("Must have been the wind...")
-        };
-        ranked.push((rank, hand.clone(), bid.clone()));
+pub mod generate;
+
+const CARDS: &str = "23456789TJQKA";
+
+pub use aoc_common::read_file;
+
+fn rank(counts: &HashMap<usize, usize>) -> usize {
+    let mut counts_vec = counts.values().cloned().collect::<Vec<usize>>();
+    counts_vec.sort_by(|a, b| b.cmp(a));
+    match counts_vec.as_slice() {
+        [5]             => 7,
+        [4, 1]          => 6,
+        [3, 2]          => 5,
+        [3, 1, 1]       => 4,
+        [2, 2, 1]       => 3,
+        [2, 1, 1, 1]    => 2,
+        [1, 1, 1, 1, 1] => 1,
+        _               => unreachable!("a hand always holds five cards")
     }
-    
+}
+
+fn winnings(mut ranked: Vec<(usize, Vec<usize>, usize)>) -> Result<usize> {
     ranked.sort_by_key(|&(first, ref second, _)| (first, second.clone()));
 
-    let result: usize = (1..=ranked.len()).collect::<Vec<usize>>().iter().zip(ranked.iter())
-        .map(|(i, (_, _, bid))| i*bid).sum();
+    checked::try_sum((1..=ranked.len()).collect::<Vec<usize>>().iter().zip(ranked.iter())
+        .map(|(i, (_, _, bid))| i.checked_mul(*bid).ok_or_else(checked::overflow)))
+}
+
+type Hand = ([char; 5], usize);
+
+fn hand(source: Source) -> Result<Hand> {
+    let (hand, bid) = source.split_once(source.contents(), " ")?;
+    if let Some((i, c)) = hand.char_indices().find(|&(_, c)| !CARDS.contains(c)) {
+        return Err(source.error(&hand[i..i + c.len_utf8()], format!("a card (one of {})", CARDS)));
+    }
+    if hand.len() != 5 {
+        return Err(source.error(hand, "a hand of five cards"));
+    }
 
-    Some(result)
+    let mut cards = ['2'; 5];
+    for (card, c) in cards.iter_mut().zip(hand.chars()) {
+        *card = c;
+    }
+    Ok((cards, source.number(bid.trim())?))
 }
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let tokens: [char; 13] = ['J', '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'Q', 'K', 'A'];
-    let token_map: HashMap<char, usize> = tokens.iter().enumerate().map(|(i, &token)| (token, i)).collect();
-
-    let hands: Vec<_> = contents.lines()
-        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
-        .map(|chunk| (chunk[0].chars().map(|s| *token_map.get(&s).unwrap()).collect::<Vec<usize>>(),
-                      chunk[1].parse::<usize>().expect("Parse failed! NaN")))
-        .collect();
-
-    let mut ranked: Vec<(usize, Vec<usize>, usize)> = Vec::new();
-    for (hand, bid) in &hands {
-        let mut counts: HashMap<usize, usize> = HashMap::new();
-        for &c in hand {
-            *counts.entry(c).or_insert(0) += 1;
-        }
+pub struct Day7;
 
-        match &counts.get(&0) {
-             None           => (),
-             Some(5)        => (),
-             Some(jokers)  => {
-                 let best = counts.iter().filter(|&(key, _)| *key != 0).max_by_key(|&(_, value)| value).unwrap().0.clone();
-                 *counts.get_mut(&best).unwrap() += **jokers;
-                 counts.remove(&0);
-             },
-        };
-
-        let mut counts_vec = counts.values().cloned().collect::<Vec<usize>>();
-        counts_vec.sort_by(|a, b| b.cmp(a));
-        let rank: usize = match counts_vec.as_slice() {
-            [5]             => 7,
-            [4, 1]          => 6,
-            [3, 2]          => 5,
-            [3, 1, 1]       => 4,
-            [2, 2, 1]       => 3,
-            [2, 1, 1, 1]    => 2,
-            [1, 1, 1, 1, 1] => 1,
-            _               => panic!("Must have been the wind...")
-        };
-
-        ranked.push((rank, hand.clone(), bid.clone()));
+impl Solution for Day7 {
+    type Input = Vec<Hand>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        stream::parse_lines(contents.as_bytes(), stream::IN_MEMORY, hand).collect()
     }
 
-    ranked.sort_by_key(|&(first, ref second, _)| (first, second.clone()));
+    fn part_1(&self, hands: &Self::Input) -> Result<Answer> {
+        let tokens: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
+        let token_map: HashMap<char, usize> = tokens.iter().enumerate().map(|(i, &token)| (token, i)).collect();
+
+        let mut ranked: Vec<(usize, Vec<usize>, usize)> = Vec::new();
+        for (hand, bid) in hands {
+            let hand: Vec<usize> = hand.iter().map(|s| *token_map.get(s).unwrap()).collect();
+            let mut counts: HashMap<usize, usize> = HashMap::new();
+            for &c in &hand {
+                *counts.entry(c).or_insert(0) += 1;
+            }
+            ranked.push((rank(&counts), hand, *bid));
+        }
 
-    let result: usize = (1..=ranked.len()).collect::<Vec<usize>>().iter().zip(ranked.iter())
-        .map(|(i, (_, _, bid))| i*bid).sum();
+        Ok(winnings(ranked)?.into())
+    }
 
-    Some(result)
+    fn part_2(&self, hands: &Self::Input) -> Result<Answer> {
+        let tokens: [char; 13] = ['J', '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'Q', 'K', 'A'];
+        let token_map: HashMap<char, usize> = tokens.iter().enumerate().map(|(i, &token)| (token, i)).collect();
+
+        let mut ranked: Vec<(usize, Vec<usize>, usize)> = Vec::new();
+        for (hand, bid) in hands {
+            let hand: Vec<usize> = hand.iter().map(|s| *token_map.get(s).unwrap()).collect();
+            let mut counts: HashMap<usize, usize> = HashMap::new();
+            for &c in &hand {
+                *counts.entry(c).or_insert(0) += 1;
+            }
+
+            match counts.get(&0).copied() {
+                 None           => (),
+                 Some(5)        => (),
+                 Some(jokers)  => {
+                     let best = *counts.iter().filter(|&(key, _)| *key != 0).max_by_key(|&(_, value)| value).unwrap().0;
+                     *counts.get_mut(&best).unwrap() += jokers;
+                     counts.remove(&0);
+                 },
+            };
+
+            ranked.push((rank(&counts), hand, *bid));
+        }
+
+        Ok(winnings(ranked)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::fixture;
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day7.parse(&input).unwrap();
 
-        let result = part_1(&input).unwrap();
-        assert_eq!(result, 6440);
+        let result = Day7.part_1(&input).unwrap();
+        assert_eq!(result, 6440.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day7.parse(&input).unwrap();
+
+        let result = Day7.part_2(&input).unwrap();
+        assert_eq!(result, 5905.into());
+    }
 
-        let result = part_2(&input).unwrap();
-        assert_eq!(result, 5905);
+    #[test]
+    fn unknown_card_is_located() {
+        let input = "32T3K 765\nT55X5 684\n";
+
+        match Day7.parse(input) {
+            Err(aoc_common::Error::Parse(err)) => {
+                assert_eq!((err.line, err.column), (2, 4));
+                assert_eq!(err.found, "X");
+            },
+            other => panic!("unexpected {:?}", other.map(|_| ())),
+        }
     }
 }
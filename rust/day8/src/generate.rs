@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use aoc_common::generate::{Generated, Rng};
+
+use aoc_common::math::lcm;
+
+const PRIMES: [usize; 12] = [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+// A fresh three-letter name ending in one of `last`.
+fn name(rng: &mut Rng, used: &mut HashSet<String>, last: &[u8]) -> String {
+    loop {
+        let name = format!("{}{}{}", (b'A' + rng.range(0..26) as u8) as char, (b'A' + rng.range(0..26) as u8) as char, *rng.pick(last) as char);
+        if used.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+// A network of about `size` nodes (at most some 16,000) built like the real
+// ones: one to six ghosts, AAA among them, each walking a loop from its `..A`
+// node to its `..Z` node and round again. Every loop is a distinct prime
+// number of passes over the instructions, so a node is always left by the
+// same instruction and the other branch is a decoy never taken. A ghost
+// therefore first reaches its `..Z` after exactly its loop length, and all
+// of them together after the least common multiple of those lengths.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let mut primes = PRIMES;
+    rng.shuffle(&mut primes);
+    let primes = &primes[..rng.range(1..7)];
+    let passes = (size.min(16_000) / primes.iter().sum::<usize>()).max(1);
+    let orders: Vec<u8> = (0..passes).map(|_| if rng.chance(1, 2) { b'L' } else { b'R' }).collect();
+
+    let middle: Vec<u8> = (b'B'..=b'Y').collect();
+    let mut used: HashSet<String> = ["AAA", "ZZZ"].map(String::from).into();
+    let mut lines = Vec::new();
+    let mut lengths = Vec::new();
+
+    for (ghost, prime) in primes.iter().enumerate() {
+        let length = prime * passes;
+        let (start, end) = match ghost {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (name(&mut rng, &mut used, b"A"), name(&mut rng, &mut used, b"Z")),
+        };
+        let mut path = vec![start];
+        path.extend((1..length).map(|_| name(&mut rng, &mut used, &middle)));
+        path.push(end);
+
+        for step in 0..=length {
+            // The end leads back round the loop, exactly as the start does.
+            let next = path[if step == length { 1 } else { step + 1 }].clone();
+            let decoy = rng.pick(&path[1..]).clone();
+            let (left, right) = match orders[step % passes] {
+                b'L' => (next, decoy),
+                _    => (decoy, next),
+            };
+            lines.push(format!("{} = ({}, {})\n", path[step], left, right));
+        }
+        lengths.push(length);
+    }
+
+    rng.shuffle(&mut lines);
+    let input = format!("{}\n\n{}", String::from_utf8_lossy(&orders), lines.concat());
+    Generated::new(input, lengths[0], lengths.iter().try_fold(1, |acc, &length| lcm(acc, length))
+        .expect("six primes times 16,000 fit in a usize"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day8;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day8, &generate(seed, 500));
+        }
+    }
+}
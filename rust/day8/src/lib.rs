@@ -1,134 +1,246 @@
-use std::{error::Error, collections::HashMap};
+use std::collections::HashMap;
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
+use aoc_common::{budget, checked, graph::{self, Cycle, Graph, NodeId}, math, Answer, Error, Result, Solution, Source};
+
+pub mod generate;
+
+pub use aoc_common::read_file;
+
+pub struct Network {
+    pub orders: Vec<char>,
+    // A node per name, its left edge first and its right second.
+    pub nodes: Graph<String>,
 }
 
-pub fn part_1(contents: &str) -> Option<usize> {
-    let orders = contents.lines().next().unwrap().chars();
-    let mut directives: HashMap<String, Vec<String>> = HashMap::new();
+impl Network {
+    // Where a walker at `node`, about to follow the `phase`th instruction,
+    // is a step later.
+    fn next(&self, (node, phase): (NodeId, usize)) -> (NodeId, usize) {
+        let branch = match self.orders[phase] {
+            'L' => 0,
+            _   => 1,
+        };
+        (self.nodes.edges(node)[branch].0, (phase + 1) % self.orders.len())
+    }
 
-    for line in contents.lines().skip(2) {
-        let split: Vec<&str> = line.split('=')
-                 .map(|word| word.trim())
-                 .collect::<Vec<&str>>();
+    // Steps from `start` until `done`, which may never happen; each step is
+    // charged to the caller's budget so that such walks can be stopped.
+    fn walk(&self, start: NodeId, done: impl Fn(&str) -> bool) -> Result<usize> {
+        let mut state = (start, 0);
+        let mut steps: usize = 0;
 
-        let key: String = split[0].to_string();
-        let val: Vec<String> = split[1]
-            .trim_matches(|c| c == '(' || c == ')')
-            .split(", ").map(|s| s.to_string())
-            .collect();
+        while !done(self.nodes.node(state.0)) {
+            budget::step()?;
+            state = self.next(state);
+            steps += 1;
+        }
 
-        directives.insert(key, val);
+        Ok(steps)
     }
 
-    let mut location: String = "AAA".to_string();
-    let mut steps: usize = 0;
-    let mut order = orders.clone();
-    
-    while location != "ZZZ" {
-        location = match order.next() {
-            Some('L')   => {
-                steps += 1;
-                directives.get(&location).unwrap()[0].clone()
-            },
-            Some('R')   => {
-                steps += 1;
-                directives.get(&location).unwrap()[1].clone()
-            },
-            _        => {
-                order = orders.clone();
-                location
+    // The ghost at `start` as a walk that goes round a loop of nodes and
+    // instructions after `tail` steps, with the steps of its first lap on
+    // which it is on a `..Z` node.
+    fn ghost(&self, start: NodeId) -> Result<Ghost> {
+        let Cycle { tail, length } = graph::find_cycle((start, 0), |&state| self.next(state))?;
+
+        let mut ends = Vec::new();
+        let mut state = (start, 0);
+        for steps in 0..tail + length {
+            if steps >= tail && self.nodes.node(state.0).ends_with('Z') {
+                ends.push(steps);
             }
+            budget::step()?;
+            state = self.next(state);
         }
+        Ok(Ghost { start, tail, length, ends })
     }
-
-    Some(steps)
 }
 
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
+struct Ghost {
+    start: NodeId,
+    tail: usize,
+    length: usize,
+    ends: Vec<usize>,
 }
 
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
-}
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Network;
 
-pub fn part_2(contents: &str) -> Option<usize> {
-    let orders = contents.lines().next().unwrap().chars();
-    let mut directives: HashMap<String, Vec<String>> = HashMap::new();
-    let mut locs: Vec<String> = Vec::new();
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        let source = Source::new(contents);
+        let mut lines = source.lines();
 
-    for line in contents.lines().skip(2) {
-        let split: Vec<&str> = line.split('=')
-                 .map(|word| word.trim())
-                 .collect::<Vec<&str>>();
+        let first = lines.next().unwrap_or(source.end());
+        if let Some((i, c)) = first.char_indices().find(|&(_, c)| c != 'L' && c != 'R') {
+            return Err(source.error(&first[i..i + c.len_utf8()], "a direction (L or R)"));
+        }
+        if first.is_empty() {
+            return Err(source.error(first, "a line of L/R directions"));
+        }
+        let orders: Vec<char> = first.chars().collect();
 
-        let key: String = split[0].to_string();
-        if key.chars().last().expect("Something went terribly wrong!") == 'A' {
-            locs.push(key.clone());
+        match lines.next() {
+            Some("") | None => (),
+            Some(line)      => return Err(source.error(line, "an empty line")),
         }
 
-        let val: Vec<String> = split[1]
-            .trim_matches(|c| c == '(' || c == ')')
-            .split(", ").map(|s| s.to_string())
-            .collect();
+        let mut nodes: Graph<String> = Graph::new();
+        let mut ids: HashMap<&str, NodeId> = HashMap::new();
+        let mut links: Vec<(&str, &str)> = Vec::new();
+
+        for line in lines {
+            let (key, val) = source.split_once(line, " = ")?;
+            let val = source.strip_prefix(val, "(")?;
+            let (left, right) = source.split_once(val, ", ")?;
+            let right = right.strip_suffix(')')
+                .ok_or_else(|| source.error(source.after(right), "\")\""))?;
+
+            // A node defined again keeps its place but takes the new links.
+            match ids.get(key) {
+                Some(&id) => links[id] = (left, right),
+                None      => {
+                    ids.insert(key, nodes.add_node(key.to_string()));
+                    links.push((left, right));
+                },
+            }
+        }
 
-        directives.insert(key, val);
+        for (id, &(left, right)) in links.iter().enumerate() {
+            for target in [left, right] {
+                let &to = ids.get(target).ok_or_else(|| source.error(target, "a node defined in the network"))?;
+                nodes.add_edge(id, to);
+            }
+        }
+
+        Ok(Network { orders, nodes })
     }
 
-    let mut all_steps: Vec<usize> = vec![0; locs.len()];
+    fn part_1(&self, network: &Self::Input) -> Result<Answer> {
+        let start = network.nodes.find(&"AAA".to_string())
+            .ok_or_else(|| Error::Unsolvable("the network has no node AAA".to_string()))?;
+        Ok(network.walk(start, |location| location == "ZZZ")?.into())
+    }
 
-    for i in 0..locs.len() {
-        let mut order = orders.clone();
-        while locs[i].chars().last().unwrap() != 'Z' {
-            locs[i] = match order.next() {
-                Some('L')   => {
-                    all_steps[i] += 1;
-                    directives.get(&locs[i]).unwrap()[0].clone()
-                },
-                Some('R')   => {
-                    all_steps[i] += 1;
-                    directives.get(&locs[i]).unwrap()[1].clone()
-                },
-                _        => {
-                    order = orders.clone();
-                    locs[i].clone()
+    fn part_2(&self, network: &Self::Input) -> Result<Answer> {
+        let mut locs: Vec<NodeId> = network.nodes.nodes()
+            .filter(|(_, name)| name.ends_with('A'))
+            .map(|(id, _)| id)
+            .collect();
+        locs.sort_by_key(|&id| network.nodes.node(id));
+        if locs.is_empty() {
+            return Err(Error::Unsolvable("the network has no ..A nodes".to_string()));
+        }
+
+        let ghosts: Vec<Ghost> = locs.iter()
+            .map(|&loc| network.ghost(loc))
+            .collect::<Result<_>>()?;
+
+        // Before the last ghost reaches its loop, walk them all.
+        let tail = ghosts.iter().map(|ghost| ghost.tail).max().unwrap_or(0);
+        let mut states: Vec<(NodeId, usize)> = ghosts.iter().map(|ghost| (ghost.start, 0)).collect();
+        for step in 0..tail {
+            if states.iter().all(|&(node, _)| network.nodes.node(node).ends_with('Z')) {
+                return Ok(step.into());
+            }
+            budget::steps(states.len() as u64)?;
+            states.iter_mut().for_each(|state| *state = network.next(*state));
+        }
+
+        // After that each is on a `..Z` node at the steps `end + k * length`
+        // for any of the ends of its lap; the answer is the earliest step one
+        // end of every ghost shares, from `tail` on.
+        let mut solutions = vec![(0_i128, 1_i128)];
+        for ghost in &ghosts {
+            let mut next = Vec::new();
+            for &solution in &solutions {
+                for &end in &ghost.ends {
+                    budget::step()?;
+                    next.extend(math::crt([solution, (end as i128, ghost.length as i128)])?);
                 }
             }
+            solutions = next;
         }
+        let tail = tail as i128;
+        let step = solutions.into_iter()
+            .map(|(step, period)| (step - tail).rem_euclid(period).checked_add(tail).ok_or_else(checked::overflow))
+            .collect::<Result<Vec<i128>>>()?
+            .into_iter()
+            .min()
+            .ok_or_else(|| Error::Unsolvable("the ghosts are never all on ..Z nodes at once".to_string()))?;
+
+        usize::try_from(step).map(|step| step.into()).map_err(|_| checked::overflow())
     }
-
-    Some(all_steps.iter().cloned().fold(1, |a, b| lcm(a, b)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{budget::Budget, fixture};
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test-1.dat").unwrap();
+        let input: String = read_file(fixture!("test-1.dat")).unwrap();
+        let input = Day8.parse(&input).unwrap();
 
-        let result = part_1(&input);
-        assert_eq!(result.unwrap(), 2);
+        let result = Day8.part_1(&input);
+        assert_eq!(result.unwrap(), 2.into());
 
-        let input: String = read_file("./src/test-2.dat").unwrap();
+        let input: String = read_file(fixture!("test-2.dat")).unwrap();
+        let input = Day8.parse(&input).unwrap();
 
-        let result = part_1(&input);
-        assert_eq!(result.unwrap(), 6);
+        let result = Day8.part_1(&input);
+        assert_eq!(result.unwrap(), 6.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test-3.dat").unwrap();
+        let input: String = read_file(fixture!("test-3.dat")).unwrap();
+        let input = Day8.parse(&input).unwrap();
+
+        let result = Day8.part_2(&input);
+        assert_eq!(result.unwrap(), 6.into());
+    }
+
+    #[test]
+    fn ghosts_may_reach_their_ends_out_of_step() {
+        // 11A first reaches 11Z after 2 steps and every 2 after that; 22A
+        // after 1 step and every 3.
+        let input = Day8.parse("L\n\n11A = (11B, XXX)\n11B = (11Z, XXX)\n11Z = (11C, XXX)\n11C = (11Z, XXX)\n\
+            22A = (22Z, XXX)\n22Z = (22B, XXX)\n22B = (22C, XXX)\n22C = (22Z, XXX)\nXXX = (XXX, XXX)\n").unwrap();
+        assert_eq!(Day8.part_2(&input).unwrap(), 4.into());
+
+        // Never together: 11A on odd steps only, 22A on even ones.
+        let input = Day8.parse("L\n\n11A = (11Z, XXX)\n11Z = (11B, XXX)\n11B = (11Z, XXX)\n\
+            22A = (22B, XXX)\n22B = (22Z, XXX)\n22Z = (22C, XXX)\n22C = (22Z, XXX)\nXXX = (XXX, XXX)\n").unwrap();
+        assert!(matches!(Day8.part_2(&input), Err(Error::Unsolvable(_))));
+    }
+
+    #[test]
+    fn ghosts_may_finish_before_their_loop() {
+        // 11Z comes once, on the way to XXX; 22A is always on a ..Z node.
+        let input = Day8.parse("L\n\n11A = (11Z, XXX)\n11Z = (XXX, XXX)\nXXX = (XXX, XXX)\n").unwrap();
+        assert_eq!(Day8.part_2(&input).unwrap(), 1.into());
+        let input = Day8.parse("L\n\n11A = (11B, XXX)\n11B = (11Z, XXX)\n11Z = (XXX, XXX)\n22A = (22Z, 22Z)\n22Z = (22Z, 22Z)\nXXX = (XXX, XXX)\n").unwrap();
+        assert_eq!(Day8.part_2(&input).unwrap(), 2.into());
+    }
+
+    #[test]
+    fn ghosts_may_pass_several_ends_a_lap() {
+        // 11A's loop of 5 has ..Z nodes 1 and 3 steps in; 22A is on one every
+        // 4 steps from step 4: 11A is there at 1, 3, 6, 8, 11, 13, 16...
+        let input = Day8.parse("L\n\n11A = (11Z, XXX)\n11Z = (11B, XXX)\n11B = (22Z, XXX)\n22Z = (11C, XXX)\n11C = (11A, XXX)\n\
+            22A = (22B, XXX)\n22B = (22C, XXX)\n22C = (22D, XXX)\n22D = (33Z, XXX)\n33Z = (22B, XXX)\nXXX = (XXX, XXX)\n").unwrap();
+        assert_eq!(Day8.part_2(&input).unwrap(), 8.into());
+    }
+
+    #[test]
+    fn unreachable_target_exhausts_the_budget() {
+        let input = Day8.parse("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n").unwrap();
 
-        let result = part_2(&input);
-        assert_eq!(result.unwrap(), 6);
+        let result = Budget::unlimited().steps(1_000).run(|| Day8.part_1(&input));
+        assert!(matches!(result, Err(Error::BudgetExceeded(_))));
     }
 }
@@ -0,0 +1,43 @@
+use aoc_common::generate::{Generated, Rng};
+
+// `x choose k` for any integer `x`, so that the polynomial can be evaluated
+// one step before the sequence starts as well as after it ends.
+fn choose(x: i64, k: usize) -> i64 {
+    (0..k as i64).fold(1, |product, i| product * (x - i) / (i + 1))
+}
+
+// `size` sequences of 5 to 21 values, each a polynomial of degree at most six
+// in Newton form, so the value after the last and before the first are the
+// polynomial evaluated there.
+pub fn generate(seed: u64, size: usize) -> Generated {
+    let mut rng = Rng::new(seed);
+    let (mut input, mut part_1, mut part_2) = (String::new(), 0, 0);
+
+    for _ in 0..size {
+        let length = rng.range(5..22);
+        let coefficients: Vec<i64> = (0..rng.range(1..(length - 1).min(7) + 1)).map(|_| rng.range(0..21) as i64 - 10).collect();
+        let value = |x: i64| coefficients.iter().enumerate().map(|(k, &a)| a * choose(x, k)).sum::<i64>();
+
+        let values: Vec<String> = (0..length as i64).map(|x| value(x).to_string()).collect();
+        input += &format!("{}\n", values.join(" "));
+        part_1 += value(length as i64);
+        part_2 += value(-1);
+    }
+
+    Generated::new(input, part_1, part_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::generate::assert_solves;
+
+    use crate::Day9;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for seed in 0..20 {
+            assert_solves(&Day9, &generate(seed, 200));
+        }
+    }
+}
@@ -1,88 +1,130 @@
-use std::error::Error;
+use std::io::BufRead;
 
-pub fn read_file(filename: &str) -> Result<String, Box<dyn Error>> {
-    let contents: String = std::fs::read_to_string(filename).unwrap();
-    Ok(contents)
-}
+use aoc_common::{checked, stream, Answer, Result, Solution, Source, Streaming};
+
+pub mod generate;
 
-pub fn part_1(contents: &str) -> Option<isize> {
-    let inputs: Vec<Vec<isize>> = contents.lines()
-        .map(|line| line.split_whitespace().map(|word| word.parse().expect("Parsing failed! NaN"))
-             .collect::<Vec<isize>>())
-        .collect();
+pub use aoc_common::read_file;
 
-    let mut triangles: Vec<Vec<Vec<isize>>> = inputs.iter().map(|seq| vec![seq.clone()]).collect();
+// The sequence followed by its rows of differences, down to a row of zeros.
+fn triangle(seq: &[isize]) -> Result<Vec<Vec<isize>>> {
+    let mut triangle: Vec<Vec<isize>> = vec![seq.to_vec()];
+    let mut done: bool = false;
 
-    for i in 0..inputs.len() {
-        let mut done: bool = false;
+    while !done {
+        let next_seq: Vec<isize> = triangle.last().expect("Something went terribly wrong!")
+            .windows(2)
+            .map(|pair| pair[1].checked_sub(pair[0]).ok_or_else(checked::overflow))
+            .collect::<Result<Vec<isize>>>()?;
 
-        while !done {
-            let next_seq: Vec<isize> = triangles[i].last().expect("Something went terribly wrong!")
-                .windows(2)
-                .map(|pair| pair[1] - pair[0])
-                .collect::<Vec<isize>>();
-            
-            triangles[i].push(next_seq.clone());
+        triangle.push(next_seq.clone());
 
-            done = next_seq.iter().all(|&x| x == 0);
-        }
+        done = next_seq.iter().all(|&x| x == 0);
     }
 
-    Some(triangles.iter()
-        .map(|set| set.iter()
-             .map(|seq| *seq.last().unwrap())
-             .fold(0, |acc, v| v + acc))
-        .sum())
+    Ok(triangle)
 }
 
-pub fn part_2(contents: &str) -> Option<isize> {
-    let inputs: Vec<Vec<isize>> = contents.lines()
-        .map(|line| line.split_whitespace().map(|word| word.parse().expect("Parsing failed! NaN"))
-             .collect::<Vec<isize>>())
-        .collect();
+fn next_value(seq: &[isize]) -> Result<isize> {
+    checked::sum(triangle(seq)?.iter()
+        .map(|seq| seq.last().copied().unwrap_or(0)))
+}
 
-    let mut triangles: Vec<Vec<Vec<isize>>> = inputs.iter().map(|seq| vec![seq.clone()]).collect();
+fn previous_value(seq: &[isize]) -> Result<isize> {
+    triangle(seq)?.iter()
+        .map(|seq| seq.first().copied().unwrap_or(0))
+        .rev()
+        .try_fold(0_isize, |acc, v| v.checked_sub(acc))
+        .ok_or_else(checked::overflow)
+}
 
-    for i in 0..inputs.len() {
-        let mut done: bool = false;
+fn sequence(source: Source) -> Result<Vec<isize>> {
+    match source.numbers::<isize>(source.contents())? {
+        seq if seq.is_empty() => Err(source.error(source.contents(), "a sequence of numbers")),
+        seq                   => Ok(seq),
+    }
+}
 
-        while !done {
-            let next_seq: Vec<isize> = triangles[i].last().expect("Something went terribly wrong!")
-                .windows(2)
-                .map(|pair| pair[1] - pair[0])
-                .collect::<Vec<isize>>();
-            
-            triangles[i].push(next_seq.clone());
+pub struct Day9;
 
-            done = next_seq.iter().all(|&x| x == 0);
-        }
+impl Day9 {
+    // Sequences parsed one line at a time from any reader.
+    pub fn sequences(reader: impl BufRead, path: &str) -> impl Iterator<Item = Result<Vec<isize>>> {
+        stream::parse_lines(reader, path, sequence)
     }
+}
 
-    Some(triangles.iter()
-        .map(|set| set.iter()
-             .map(|seq| *seq.first().unwrap())
-             .rev()
-             .fold(0, |acc, v| v - acc))
-        .sum())
+impl Solution for Day9 {
+    type Input = Vec<Vec<isize>>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        Self::sequences(contents.as_bytes(), stream::IN_MEMORY).collect()
+    }
+
+    fn part_1(&self, inputs: &Self::Input) -> Result<Answer> {
+        Ok(checked::try_sum(inputs.iter().map(|seq| next_value(seq)))?.into())
+    }
+
+    fn part_2(&self, inputs: &Self::Input) -> Result<Answer> {
+        Ok(checked::try_sum(inputs.iter().map(|seq| previous_value(seq)))?.into())
+    }
+}
+
+impl Streaming for Day9 {
+    fn stream_part_1(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        Ok(checked::try_sum(Self::sequences(reader, path).map(|seq| seq.and_then(|seq| next_value(&seq))))?.into())
+    }
+
+    fn stream_part_2(&self, reader: &mut dyn BufRead, path: &str) -> Result<Answer> {
+        Ok(checked::try_sum(Self::sequences(reader, path).map(|seq| seq.and_then(|seq| previous_value(&seq))))?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{fixture, property::{self, ensure}};
     
     #[test]
     fn part_1_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day9.parse(&input).unwrap();
 
-        let result = part_1(&input);
-        assert_eq!(result.unwrap(), 114);
+        let result = Day9.part_1(&input);
+        assert_eq!(result.unwrap(), 114.into());
     }
 
     #[test]
     fn part_2_works() {
-        let input: String = read_file("./src/test.dat").unwrap();
+        let input: String = read_file(fixture!("test.dat")).unwrap();
+        let input = Day9.parse(&input).unwrap();
+
+        let result = Day9.part_2(&input);
+        assert_eq!(result.unwrap(), 2.into());
+    }
+
+    #[test]
+    fn streaming_matches_parsed() {
+        let input: String = read_file(fixture!("test.dat")).unwrap();
 
-        let result = part_2(&input);
-        assert_eq!(result.unwrap(), 2);
+        assert_eq!(Day9.stream_part_1(&mut input.as_bytes(), stream::IN_MEMORY).unwrap(), 114.into());
+        assert_eq!(Day9.stream_part_2(&mut input.as_bytes(), stream::IN_MEMORY).unwrap(), 2.into());
+    }
+
+    #[test]
+    fn polynomials_extrapolate_exactly() {
+        // A polynomial of degree `k` has constant `k`th differences, so any
+        // `k + 2` of its values pin down the ones either side.
+        property::check("day9 polynomials", 8, |rng, size| {
+            let degree = rng.range(0..size);
+            let coefficients: Vec<isize> = (0..=degree).map(|_| rng.range(0..21) as isize - 10).collect();
+            let value = |x: isize| coefficients.iter().rev().fold(0, |acc, &a| acc * x + a);
+            let length = degree + 2 + rng.range(0..10);
+            let seq: Vec<isize> = (0..length as isize).map(value).collect();
+
+            let (next, previous) = (next_value(&seq).unwrap(), previous_value(&seq).unwrap());
+            ensure(next == value(length as isize), || format!("next of {:?} is {}, not {}", seq, next, value(length as isize)))?;
+            ensure(previous == value(-1), || format!("previous of {:?} is {}, not {}", seq, previous, value(-1)))
+        });
     }
 }